@@ -68,7 +68,15 @@ fn main() {
         "auto_init",
         "auto_init_random",
         "bluetil_ad",
+        "core_cond",
+        "core_mbox",
         "core_msg",
+        "core_msg_bus",
+        "core_thread_flags",
+        "event",
+        "event_thread",
+        "event_thread_highest",
+        "event_thread_medium",
         "gcoap",
         "gnrc",
         "gnrc_icmpv6",
@@ -88,6 +96,8 @@ fn main() {
         "gnrc_pktbuf",
         "gnrc_udp",
         "ipv6",
+        "isrpipe",
+        "isrpipe_read_timeout",
         "microbit",
         "nimble_host",
         "periph_adc",
@@ -104,9 +114,11 @@ fn main() {
         "periph_uart_rxstart_irq",
         "periph_uart_tx_ondemand",
         "prng_shaxprng",
-        "pthread",
         "random",
         "saul",
+        "sched_cb",
+        "schedstatistics",
+        "sema",
         "shell",
         "sock",
         "sock_aux_local",
@@ -122,6 +134,10 @@ fn main() {
         "ztimer_periodic",
         "ztimer_sec",
         "ztimer_usec",
+        "ztimer64",
+        "ztimer64_msec",
+        "ztimer64_sec",
+        "ztimer64_usec",
     ];
 
     for module in known_modules {
@@ -0,0 +1,87 @@
+//! Condition variables built using RIOT's [cond](https://doc.riot-os.org/group__core__sync__cond.html)
+//! module
+//!
+//! This roughly mimics [std::sync::Condvar], and works with the [Mutex](crate::mutex::Mutex) of
+//! this crate.
+//!
+//! [std::sync::Condvar]: https://doc.rust-lang.org/std/sync/struct.Condvar.html
+
+use core::cell::UnsafeCell;
+
+use crate::mutex::MutexGuard;
+
+/// A condition variable, through which threads can wait for a change in data protected by a
+/// [Mutex](crate::mutex::Mutex).
+///
+/// Unlike the [std::sync::Condvar], there is no check whether the condition variable is used with
+/// different mutexes. As with any condition variable, the awaited condition should be checked in a
+/// loop (or by using [`.wait_while()`](Self::wait_while)), as another thread may have changed the
+/// data before the woken thread gets the mutex back.
+///
+/// [std::sync::Condvar]: https://doc.rust-lang.org/std/sync/struct.Condvar.html
+pub struct Condvar {
+    cond: UnsafeCell<riot_sys::cond_t>,
+}
+
+// unsafe: All access to the cond_t goes through thread safe C functions
+unsafe impl Send for Condvar {}
+unsafe impl Sync for Condvar {}
+
+impl Condvar {
+    /// Create a new condition variable
+    #[doc(alias = "cond_init")]
+    pub const fn new() -> Self {
+        Condvar {
+            // unsafe: An all-zero cond_t is one without waiting threads (as in COND_INIT)
+            cond: UnsafeCell::new(unsafe { core::mem::zeroed() }),
+        }
+    }
+
+    /// Unlock the mutex behind the guard and block until the condition variable is notified; then,
+    /// lock the mutex again and return the guard.
+    ///
+    /// Note that this takes a [MutexGuard], which can only be obtained in a thread context.
+    #[doc(alias = "cond_wait")]
+    pub fn wait<'a, T>(&self, guard: MutexGuard<'a, T>) -> MutexGuard<'a, T> {
+        // unsafe: The mutex is locked by the current thread as attested by the guard, which is
+        // held on to during the wait (and is logically locked again when the function returns).
+        // The guard's existence implies that this is running in a thread.
+        unsafe { riot_sys::cond_wait(self.cond.get(), crate::inline_cast_mut(guard.raw_mutex())) };
+        guard
+    }
+
+    /// Wait on the condition variable (as in [`.wait()`](Self::wait)) as long as the condition
+    /// holds for the protected data.
+    ///
+    /// The condition is checked once before waiting, and again every time the thread is woken.
+    pub fn wait_while<'a, T, F: FnMut(&mut T) -> bool>(
+        &self,
+        mut guard: MutexGuard<'a, T>,
+        mut condition: F,
+    ) -> MutexGuard<'a, T> {
+        while condition(&mut *guard) {
+            guard = self.wait(guard);
+        }
+        guard
+    }
+
+    /// Wake up the highest priority thread waiting on the condition variable, if any.
+    #[doc(alias = "cond_signal")]
+    pub fn notify_one(&self) {
+        // unsafe: C function can be called at any time on an initialized condition variable
+        unsafe { riot_sys::cond_signal(self.cond.get()) };
+    }
+
+    /// Wake up all threads waiting on the condition variable.
+    #[doc(alias = "cond_broadcast")]
+    pub fn notify_all(&self) {
+        // unsafe: C function can be called at any time on an initialized condition variable
+        unsafe { riot_sys::cond_broadcast(self.cond.get()) };
+    }
+}
+
+impl Default for Condvar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
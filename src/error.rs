@@ -150,3 +150,4 @@ E!(ENOSPC);
 E!(ENOTCONN);
 E!(ENOTSUP);
 E!(EOVERFLOW);
+E!(ETIMEDOUT);
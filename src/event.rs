@@ -0,0 +1,211 @@
+//! Wrappers for RIOT's [event queues](https://doc.riot-os.org/group__sys__event.html)
+//!
+//! An [EventQueue] is processed by a single thread that runs its
+//! [`.loop_forever()`](EventQueue::loop_forever) method; [Event]s that are bound to the queue can
+//! be posted to it from any thread or interrupt, and their handler then runs in the queue's thread.
+//!
+//! ## Lifetimes
+//!
+//! An `EventQueue<'env>` only accepts events that are around for all of `'env`, and it can only
+//! be processed by a thread that has it borrowed for all of `'env`. Thus, a queue can be created
+//! on a thread's stack (or inside a [`thread::scope`](crate::thread::scope)), and the events and
+//! their handlers can borrow from the environment; at the same time, it is ensured that no event is
+//! processed (or even linked into the queue) after its handler went out of scope. This fulfills
+//! the same purpose as the `'env` lifetime of [`thread::scope`](crate::thread::scope) and
+//! [`gcoap::scope`](crate::gcoap::scope), without needing a closure to delimit the scope.
+//!
+//! For queues that are used from interrupts, it is typically easiest to have everything
+//! `'static`, as is the case with the shared queues of the `event_thread` module (eg.
+//! [EventQueue::lowest()]).
+
+use core::cell::{Cell, UnsafeCell};
+use core::marker::PhantomData;
+
+/// A queue of events that is processed in a single thread
+///
+/// The `'env` lifetime is invariant; see the [module level documentation](self) for details.
+#[repr(transparent)]
+pub struct EventQueue<'env> {
+    queue: UnsafeCell<riot_sys::event_queue_t>,
+    _events: PhantomData<Cell<&'env ()>>,
+}
+
+// unsafe: All access to the queue goes through C functions that are thread and interrupt safe
+unsafe impl<'env> Sync for EventQueue<'env> {}
+unsafe impl<'env> Send for EventQueue<'env> {}
+
+impl<'env> EventQueue<'env> {
+    /// Create a new event queue that is not processed by any thread yet.
+    ///
+    /// Events can already be posted to a queue created like this; they will be processed once a
+    /// thread starts looping over the queue.
+    #[doc(alias = "event_queue_init_detached")]
+    pub const fn new() -> Self {
+        EventQueue {
+            // unsafe: A zeroed event queue is an empty detached queue
+            // (EVENT_QUEUE_INIT_DETACHED)
+            queue: UnsafeCell::new(unsafe { core::mem::zeroed() }),
+            _events: PhantomData,
+        }
+    }
+
+    /// Make the current thread process the queue's events, forever.
+    ///
+    /// As the queue uses the `THREAD_FLAG_EVENT` flag to wake up its thread, that flag is claimed
+    /// from the thread's flag semantics.
+    ///
+    /// # Panics
+    ///
+    /// ... if the queue is already processed by a thread, or when the `THREAD_FLAG_EVENT` flag is
+    /// already in use.
+    #[doc(alias = "event_loop")]
+    pub fn loop_forever(&'env self, flags: &mut crate::thread::flags::FlagSemantics) -> ! {
+        // The flag is set by event_post through the C API, and waited for by event_wait_multi;
+        // we only hold these to keep anyone else from using the flag.
+        let (_receiver, _sender) = flags.split_off_specific(riot_sys::THREAD_FLAG_EVENT as _);
+
+        crate::interrupt::free(|_| {
+            // unsafe: Only reading, and in a critical section against concurrent claims
+            let waiter = unsafe { (*self.queue.get()).waiter };
+            assert!(
+                waiter.is_null(),
+                "Event queue is already processed by another thread"
+            );
+            // unsafe: Queue is detached, as just checked
+            unsafe {
+                riot_sys::inline::event_queue_claim(crate::inline_cast_mut(self.queue.get()))
+            };
+        });
+
+        loop {
+            // unsafe: The queue is claimed by this thread
+            let event = unsafe { riot_sys::event_wait_multi(self.queue.get(), 1) };
+            // unsafe: Events only get into the queue through Event::post, which sets a handler
+            // that can deal with the event
+            unsafe {
+                let handler = (*event)
+                    .handler
+                    .expect("Events are always posted with a handler");
+                handler(event);
+            }
+        }
+    }
+}
+
+#[cfg(riot_module_event_thread_highest)]
+impl EventQueue<'static> {
+    /// The shared queue that is processed by the `event_thread` module at highest priority
+    #[doc(alias = "EVENT_PRIO_HIGHEST")]
+    pub fn highest() -> &'static Self {
+        Self::shared(riot_sys::event_queue_prio_t_EVENT_QUEUE_PRIO_HIGHEST as _)
+    }
+}
+
+#[cfg(riot_module_event_thread_medium)]
+impl EventQueue<'static> {
+    /// The shared queue that is processed by the `event_thread` module at medium priority
+    #[doc(alias = "EVENT_PRIO_MEDIUM")]
+    pub fn medium() -> &'static Self {
+        Self::shared(riot_sys::event_queue_prio_t_EVENT_QUEUE_PRIO_MEDIUM as _)
+    }
+}
+
+#[cfg(riot_module_event_thread)]
+impl EventQueue<'static> {
+    /// The shared queue that is processed by the `event_thread` module at lowest priority
+    #[doc(alias = "EVENT_PRIO_LOWEST")]
+    pub fn lowest() -> &'static Self {
+        Self::shared(riot_sys::event_queue_prio_t_EVENT_QUEUE_PRIO_LOWEST as _)
+    }
+
+    fn shared(index: usize) -> &'static Self {
+        // unsafe: The queues are statically allocated, only ever accessed through C functions,
+        // and are processed by the event threads forever. EventQueue is repr(transparent).
+        unsafe {
+            &*(core::ptr::addr_of!(riot_sys::event_thread_queues[index])
+                as *const EventQueue<'static>)
+        }
+    }
+}
+
+/// The part of an [Event] that is reachable from the C event pointer
+#[repr(C)]
+struct Inner<H> {
+    // Must be the first field, as the handler casts a pointer to it into a pointer to Self.
+    event: UnsafeCell<riot_sys::event_t>,
+    handler: UnsafeCell<H>,
+}
+
+/// An event that can be posted to a particular [EventQueue], with a handler that runs in the
+/// queue's thread.
+///
+/// The handler is any `FnMut() + Send`; a trait object can be used by passing a `&mut (dyn
+/// FnMut() + Send)`.
+///
+/// ## Posted events
+///
+/// A posted event stays linked into its queue until its handler is about to run. Rather than
+/// removing the event from the queue when it is dropped (which could race with the queue's thread
+/// just having taken it out), [`.post()`](Self::post) borrows the event for all of the queue's
+/// `'env` lifetime. Thus, an event that was ever posted can only be dropped once the queue can not
+/// be processed any more; for the shared queues (eg. [EventQueue::lowest()]), only `'static`
+/// events can be posted.
+pub struct Event<'env, H: FnMut() + Send + 'env> {
+    inner: Inner<H>,
+    queue: &'env EventQueue<'env>,
+}
+
+// unsafe: The handler is only ever accessed from the queue's thread, and the C event is only
+// accessed through C functions that are thread and interrupt safe.
+unsafe impl<'env, H: FnMut() + Send + 'env> Sync for Event<'env, H> {}
+
+impl<'env, H: FnMut() + Send + 'env> Event<'env, H> {
+    /// Create an event that, whenever it is posted, runs the handler in the queue's thread.
+    ///
+    /// The event is bound to the queue, which ensures that the handler is never run concurrently
+    /// from different threads.
+    pub fn new(queue: &'env EventQueue<'env>, handler: H) -> Self {
+        unsafe extern "C" fn handle<H: FnMut() + Send>(event: *mut riot_sys::event_t) {
+            // unsafe: The event pointer was created from the Inner's first field
+            let inner = unsafe { &*(event as *const Inner<H>) };
+            // unsafe: The handler is only ever called from the one thread that processes the queue
+            // (which is not reentrant), so this is the only reference.
+            let handler = unsafe { &mut *inner.handler.get() };
+            handler();
+        }
+
+        Event {
+            inner: Inner {
+                event: UnsafeCell::new(riot_sys::event_t {
+                    handler: Some(handle::<H>),
+                    ..Default::default()
+                }),
+                handler: UnsafeCell::new(handler),
+            },
+            queue,
+        }
+    }
+
+    /// Add the event to its queue, unless it is already pending there.
+    ///
+    /// This can be called from any thread, and from interrupts.
+    ///
+    /// As the queue may refer to the event until it has been processed, the event needs to stay
+    /// around for the full `'env` lifetime.
+    #[doc(alias = "event_post")]
+    pub fn post(&'env self) {
+        // unsafe: C function is thread and interrupt safe; lifetimes are ensured by the 'env
+        // requirements
+        unsafe { riot_sys::event_post(self.queue.queue.get(), self.inner.event.get()) };
+    }
+
+    /// Remove the event from its queue if it is pending.
+    ///
+    /// This does not stop the handler if it is currently running.
+    #[doc(alias = "event_cancel")]
+    pub fn cancel(&self) {
+        // unsafe: C function is thread and interrupt safe; canceling an event that is not queued
+        // has no effect
+        unsafe { riot_sys::event_cancel(self.queue.queue.get(), self.inner.event.get()) };
+    }
+}
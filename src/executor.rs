@@ -0,0 +1,138 @@
+//! A minimal executor that runs a single future on the current thread
+//!
+//! [block_on] polls a future until it completes; in between, the thread is parked by waiting for
+//! a thread flag. The [Waker]s it hands out set that flag on the thread, which can be done from
+//! other threads and from interrupts (as is done, for example, by
+//! [`ztimer::Clock::sleep_async()`](crate::ztimer::Clock::sleep_async)).
+//!
+//! The thread also wakes up when a message arrives in its message queue (as indicated by
+//! `THREAD_FLAG_MSG_WAITING`), so that futures that process messages get polled again.
+//!
+//! As no [StartToken](crate::thread::StartToken) is needed, this can be used in `main` as well as
+//! in threads created through [`thread::spawn`](crate::thread::spawn). The flag used for waking
+//! (`1 << 13`) is reserved for this purpose, and never handed out by a
+//! [FlagSemantics](crate::thread::flags::FlagSemantics). Code that uses the raw thread flags API
+//! in a thread that runs `block_on` must not set, clear or wait for that flag.
+//!
+//! ## Example
+//!
+//! ```
+//! # #![no_std]
+//! # #![feature(start)]
+//! # #[start]
+//! # fn main(_argc: isize, _argv: *const *const u8) -> isize { panic!("Doc tests are not supposed to be run") }
+//! # use riot_wrappers::ztimer::{Clock, Ticks};
+//! riot_wrappers::executor::block_on(async {
+//!     Clock::msec().sleep_async(Ticks(100)).await;
+//!     riot_wrappers::println!("Woke up after 100ms");
+//! });
+//! ```
+
+use core::cell::Cell;
+use core::future::Future;
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+use crate::thread::KernelPID;
+
+/// Thread flag through which the executor's wakers wake up its thread.
+///
+/// This is reserved in [FlagSemantics](crate::thread::flags::FlagSemantics), which never hands it
+/// out.
+pub(crate) const WAKE_FLAG: u16 = 1 << 13;
+
+/// Number of [block_on] calls that are currently running in each thread, indexed by PID
+///
+/// Each entry is only ever accessed by its own thread.
+static NESTING: bare_metal::Mutex<[Cell<u8>; riot_sys::KERNEL_PID_LAST as usize + 1]> =
+    bare_metal::Mutex::new([const { Cell::new(0) }; riot_sys::KERNEL_PID_LAST as usize + 1]);
+
+/// Run a future to completion on the current thread.
+///
+/// The thread blocks while the future is pending.
+///
+/// This can be nested (ie. a future run through `block_on` can call `block_on`), even though it is
+/// rarely a good idea, as the outer future can not make progress while the inner one runs. When
+/// the inner future completes, the outer is polled again at least once, as the inner executor may
+/// have consumed wake-ups meant for it.
+///
+/// # Panics
+///
+/// ... when called from an interrupt.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    crate::thread::InThread::new().expect("block_on can only be used in a thread");
+
+    let pid = crate::thread::get_pid();
+    let depth = crate::interrupt::free(|cs| {
+        let depth = &NESTING.borrow(cs)[pid.0 as usize];
+        depth.set(
+            depth
+                .get()
+                .checked_add(1)
+                .expect("block_on nested too deeply"),
+        );
+        depth.get()
+    });
+
+    let mut future = core::pin::pin!(future);
+    let waker = waker_for(pid);
+    let mut context = Context::from_waker(&waker);
+
+    let result = loop {
+        if let Poll::Ready(result) = future.as_mut().poll(&mut context) {
+            break result;
+        }
+        // unsafe: C function can be called at any time in a thread, which has been checked
+        unsafe {
+            riot_sys::thread_flags_wait_any(WAKE_FLAG | riot_sys::THREAD_FLAG_MSG_WAITING as u16)
+        };
+    };
+
+    crate::interrupt::free(|cs| NESTING.borrow(cs)[pid.0 as usize].set(depth - 1));
+    if depth > 1 {
+        // Any surrounding executor gets to check on its future.
+        waker.wake();
+    } else {
+        // Wake-ups that came in after the last poll are of no interest to anyone any more.
+        // unsafe: C function can be called at any time
+        unsafe { riot_sys::thread_flags_clear(WAKE_FLAG) };
+    }
+
+    result
+}
+
+/// Check whether a waker is the one [block_on] uses in the current thread.
+///
+/// Futures that rely on the thread flags [block_on] waits for (rather than on having their waker
+/// woken) use this to find out whether they need to wake any other executor themselves.
+#[cfg(feature = "with_msg_v2")]
+pub(crate) fn is_current_executor(waker: &Waker) -> bool {
+    waker.will_wake(&waker_for(crate::thread::get_pid()))
+}
+
+/// Create a waker that wakes the executor on the given thread.
+///
+/// The waker is represented by the PID alone; thus, it does not need to be dropped, and it can be
+/// used from interrupts.
+fn waker_for(pid: KernelPID) -> Waker {
+    // unsafe: The vtable functions only ever interpret the data as a PID
+    unsafe { Waker::from_raw(RawWaker::new(pid.0 as usize as *const (), &VTABLE)) }
+}
+
+static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake, drop_waker);
+
+fn clone(data: *const ()) -> RawWaker {
+    RawWaker::new(data, &VTABLE)
+}
+
+fn wake(data: *const ()) {
+    // The PID was valid when the waker was created, and the thread may only have ended since. (If
+    // the PID has been reused, the worst that happens is that another executor polls once
+    // spuriously).
+    let pid = KernelPID(data as usize as _);
+    if let Ok(thread) = pid.thread() {
+        // unsafe: C function can be called at any time with a valid thread
+        unsafe { riot_sys::thread_flags_set(thread as *mut _, WAKE_FLAG) };
+    }
+}
+
+fn drop_waker(_data: *const ()) {}
@@ -113,3 +113,42 @@ mod slice_to_cstr {
 }
 #[cfg(riot_module_vfs)]
 pub use slice_to_cstr::*;
+
+mod waker_slot {
+    use core::cell::Cell;
+    use core::task::Waker;
+
+    /// Storage for a single [Waker] that can be registered from a future and woken from any
+    /// context (in particular, from interrupts).
+    ///
+    /// Only one waker is kept. If a different one gets registered, the previous one is woken, so
+    /// that its task can register again when polled. (This is correct for any number of tasks, but
+    /// efficient only if it is usually just one that is waiting).
+    pub(crate) struct WakerSlot(bare_metal::Mutex<Cell<Option<Waker>>>);
+
+    impl WakerSlot {
+        pub(crate) const fn new() -> Self {
+            Self(bare_metal::Mutex::new(Cell::new(None)))
+        }
+
+        /// Store a waker to be woken at the next [`.wake()`](Self::wake).
+        pub(crate) fn register(&self, waker: &Waker) {
+            let waker = waker.clone();
+            let previous =
+                crate::interrupt::free(|cs| self.0.borrow(cs).replace(Some(waker.clone())));
+            if let Some(previous) = previous {
+                if !previous.will_wake(&waker) {
+                    previous.wake();
+                }
+            }
+        }
+
+        /// Wake the registered waker, if any.
+        pub(crate) fn wake(&self) {
+            if let Some(waker) = crate::interrupt::free(|cs| self.0.borrow(cs).take()) {
+                waker.wake();
+            }
+        }
+    }
+}
+pub(crate) use waker_slot::*;
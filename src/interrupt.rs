@@ -5,6 +5,9 @@
 //! * Utility functions can disable interrupts (creating critical sections), check whether
 //!   interrupts are enabled or to determine whether code is executed in a thread or an ISR.
 //!
+//! * An [IrqMutex] holds data that is shared between threads and interrupts, and is accessed
+//!   inside such critical sections.
+//!
 //! * Some functions (eg. [set_during](crate::thread::ValueInThread<crate::ztimer::Clock>::set_during))
 //!   take callbacks that will be called in an interrupt context.
 //!
@@ -75,3 +78,116 @@ pub fn free<R, F: FnOnce(&CriticalSection) -> R>(f: F) -> R {
     unsafe { riot_sys::irq_restore(stored) };
     ret
 }
+
+/// A data container that is shared between thread and interrupt contexts, and accessed with
+/// interrupts disabled
+///
+/// This is similar to [`bare_metal::Mutex`], but grants mutable access. It can be placed in a
+/// `static` to share state with interrupt callbacks (eg. of UART or GPIO) without resorting to
+/// `static mut`.
+///
+/// ```
+/// # #![no_std]
+/// # #![feature(start)]
+/// # #[start]
+/// # fn main(_argc: isize, _argv: *const *const u8) -> isize { panic!("Doc tests are not supposed to be run") }
+/// # use riot_wrappers::interrupt::IrqMutex;
+/// static RECEIVED: IrqMutex<u32> = IrqMutex::new(0);
+///
+/// // In an interrupt callback
+/// RECEIVED.with(|count| *count += 1);
+///
+/// // In a thread
+/// let count = RECEIVED.with(|count| core::mem::take(count));
+/// ```
+///
+/// As interrupts are disabled while the data is accessed, any work done on it should be short.
+pub struct IrqMutex<T> {
+    data: core::cell::UnsafeCell<T>,
+    // Only accessed in critical sections; guards against nested locking inside one
+    locked: core::cell::Cell<bool>,
+}
+
+// unsafe: All access happens with interrupts disabled, and is thus serialized
+unsafe impl<T: Send> Sync for IrqMutex<T> {}
+
+impl<T> IrqMutex<T> {
+    /// Create a new unlocked mutex
+    pub const fn new(value: T) -> Self {
+        IrqMutex {
+            data: core::cell::UnsafeCell::new(value),
+            locked: core::cell::Cell::new(false),
+        }
+    }
+
+    /// Get access to the data for the duration of a critical section.
+    ///
+    /// ## Panics
+    ///
+    /// ... if the data is already locked in the same critical section.
+    pub fn lock<'cs>(&'cs self, cs: &'cs CriticalSection) -> IrqMutexGuard<'cs, T> {
+        self.try_lock(cs)
+            .expect("IrqMutex is already locked in this critical section")
+    }
+
+    /// Get access to the data for the duration of a critical section, unless it is already
+    /// locked in that critical section.
+    pub fn try_lock<'cs>(&'cs self, _cs: &'cs CriticalSection) -> Option<IrqMutexGuard<'cs, T>> {
+        if self.locked.replace(true) {
+            return None;
+        }
+        Some(IrqMutexGuard { mutex: self })
+    }
+
+    /// Run a closure on the data with interrupts disabled.
+    ///
+    /// ## Panics
+    ///
+    /// ... if the data is already locked by a surrounding critical section.
+    pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        free(|cs| f(&mut self.lock(cs)))
+    }
+
+    /// Get access to the data without locking, as the mutex is exclusively borrowed
+    pub fn get_mut(&mut self) -> &mut T {
+        self.data.get_mut()
+    }
+
+    /// Take the data out of the mutex
+    pub fn into_inner(self) -> T {
+        self.data.into_inner()
+    }
+}
+
+impl<T: Default> Default for IrqMutex<T> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+/// Access to the data of an [IrqMutex], valid for the duration of a critical section
+pub struct IrqMutexGuard<'cs, T> {
+    mutex: &'cs IrqMutex<T>,
+}
+
+impl<'cs, T> core::ops::Deref for IrqMutexGuard<'cs, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // unsafe: Exclusive by the locked flag, and only used inside the critical section
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<'cs, T> core::ops::DerefMut for IrqMutexGuard<'cs, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // unsafe: Exclusive by the locked flag, and only used inside the critical section
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<'cs, T> Drop for IrqMutexGuard<'cs, T> {
+    fn drop(&mut self) {
+        self.mutex.locked.set(false);
+    }
+}
@@ -0,0 +1,216 @@
+//! Byte streams from interrupts to threads, based on RIOT's [isrpipe]
+//!
+//! A [BytePipe] is a ring buffer of bytes, typically filled from an interrupt (for example by a
+//! UART's receive callback) and read from a thread. It is used through the [Producer] and
+//! [Consumer] obtained by [`.split()`](BytePipe::split).
+//!
+//! ## Example
+//!
+//! ```
+//! # #![no_std]
+//! # #![feature(start)]
+//! # #[start]
+//! # fn main(_argc: isize, _argv: *const *const u8) -> isize { panic!("Doc tests are not supposed to be run") }
+//! # use riot_wrappers::isrpipe::BytePipe;
+//! let mut pipe: BytePipe<64> = BytePipe::new();
+//! let (producer, consumer) = pipe.split();
+//! // Typically, the producer is passed into an interrupt callback, such as the one of
+//! // `riot_wrappers::uart::UartDevice::new_scoped`.
+//! producer.write(b"Hello");
+//! let mut buf = [0; 16];
+//! let n = consumer.read(&mut buf);
+//! assert!(&buf[..n] == b"Hello");
+//! ```
+//!
+//! [isrpipe]: https://doc.riot-os.org/group__isr__pipe.html
+
+use core::cell::UnsafeCell;
+
+/// A buffer for `N` bytes that are passed from interrupts (or threads) to a thread
+///
+/// `N` needs to be a power of 2.
+pub struct BytePipe<const N: usize> {
+    pipe: UnsafeCell<riot_sys::isrpipe_t>,
+    buf: UnsafeCell<[u8; N]>,
+}
+
+// unsafe: The pipe is only accessed through the thread and interrupt safe isrpipe functions
+unsafe impl<const N: usize> Send for BytePipe<N> {}
+unsafe impl<const N: usize> Sync for BytePipe<N> {}
+
+impl<const N: usize> BytePipe<N> {
+    /// Create an empty pipe
+    pub const fn new() -> Self {
+        const { assert!(N.is_power_of_two(), "Pipe size needs to be a power of 2") };
+        BytePipe {
+            // unsafe: An all-zero isrpipe_t is a valid (if unusable) value; it is initialized for
+            // use when split.
+            pipe: UnsafeCell::new(unsafe { core::mem::zeroed() }),
+            buf: UnsafeCell::new([0; N]),
+        }
+    }
+
+    /// Obtain the writing and the reading side of the pipe
+    ///
+    /// Any data left in the pipe from an earlier split is discarded.
+    #[doc(alias = "isrpipe_init")]
+    pub fn split(&mut self) -> (Producer<'_, N>, Consumer<'_, N>) {
+        // unsafe: No other references to the pipe exist, and the buffer is owned by it. The size
+        // is checked at construction time.
+        unsafe {
+            riot_sys::isrpipe_init(
+                crate::inline_cast_mut(self.pipe.get()),
+                self.buf.get() as *mut u8,
+                N as _,
+            )
+        };
+        let pipe = &*self;
+        (Producer { pipe }, Consumer { pipe })
+    }
+
+    fn tsrb(&self) -> *mut riot_sys::tsrb_t {
+        // unsafe: Only creates a pointer
+        unsafe { core::ptr::addr_of_mut!((*self.pipe.get()).tsrb) }
+    }
+}
+
+impl<const N: usize> Default for BytePipe<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writing side of a [BytePipe]
+///
+/// All its methods can be used both from threads and from interrupts.
+pub struct Producer<'a, const N: usize> {
+    pipe: &'a BytePipe<N>,
+}
+
+impl<'a, const N: usize> Producer<'a, N> {
+    /// Put a single byte into the pipe, or return it if the pipe is full.
+    #[doc(alias = "isrpipe_write_one")]
+    pub fn write_byte(&self, byte: u8) -> Result<(), u8> {
+        // unsafe: Pipe is initialized by construction; the function may be called from any context
+        match unsafe {
+            riot_sys::isrpipe_write_one(crate::inline_cast_mut(self.pipe.pipe.get()), byte)
+        } {
+            0 => Ok(()),
+            _ => Err(byte),
+        }
+    }
+
+    /// Put as many bytes into the pipe as fit, and return how many that were.
+    #[doc(alias = "isrpipe_write")]
+    pub fn write(&self, data: &[u8]) -> usize {
+        // unsafe: Pipe is initialized by construction; the function may be called from any context
+        let written = unsafe {
+            riot_sys::isrpipe_write(
+                crate::inline_cast_mut(self.pipe.pipe.get()),
+                data.as_ptr(),
+                data.len() as _,
+            )
+        };
+        written as _
+    }
+}
+
+/// Reading side of a [BytePipe]
+pub struct Consumer<'a, const N: usize> {
+    pipe: &'a BytePipe<N>,
+}
+
+impl<'a, const N: usize> Consumer<'a, N> {
+    /// Read bytes into the buffer, blocking until at least one is available, and return how many
+    /// were read.
+    ///
+    /// This only returns 0 if the buffer is empty.
+    ///
+    /// ## Panics
+    ///
+    /// ... when called from an interrupt; use [`.try_read()`](Self::try_read) there.
+    #[doc(alias = "isrpipe_read")]
+    pub fn read(&self, buf: &mut [u8]) -> usize {
+        crate::thread::InThread::new()
+            .expect("Blocking pipe reads may only be called outside of interrupt contexts");
+        if buf.is_empty() {
+            return 0;
+        }
+        // unsafe: Pipe is initialized by construction; running in a thread as checked
+        let read = unsafe {
+            riot_sys::isrpipe_read(
+                crate::inline_cast_mut(self.pipe.pipe.get()),
+                buf.as_mut_ptr(),
+                buf.len() as _,
+            )
+        };
+        read as _
+    }
+
+    /// Like [`.read()`](Self::read), but returning an `ETIMEDOUT` error if no data arrives
+    /// within the given time.
+    ///
+    /// ## Panics
+    ///
+    /// ... when called from an interrupt.
+    #[cfg(all(riot_module_isrpipe_read_timeout, riot_module_ztimer_usec))]
+    #[doc(alias = "isrpipe_read_timeout")]
+    pub fn read_timeout(
+        &self,
+        buf: &mut [u8],
+        timeout: crate::ztimer::Ticks<1_000_000>,
+    ) -> Result<usize, crate::error::NumericError> {
+        use crate::error::NegativeErrorExt;
+
+        crate::thread::InThread::new()
+            .expect("Blocking pipe reads may only be called outside of interrupt contexts");
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        // unsafe: Pipe is initialized by construction; running in a thread as checked
+        let read = unsafe {
+            riot_sys::isrpipe_read_timeout(
+                crate::inline_cast_mut(self.pipe.pipe.get()),
+                buf.as_mut_ptr(),
+                buf.len() as _,
+                timeout.0,
+            )
+        }
+        .negative_to_error()?;
+        Ok(read as _)
+    }
+
+    /// Read whatever bytes are available into the buffer without blocking, and return how many
+    /// were read.
+    ///
+    /// This can be used from interrupts.
+    #[doc(alias = "tsrb_get")]
+    pub fn try_read(&self, buf: &mut [u8]) -> usize {
+        // unsafe: Pipe is initialized by construction; the ring buffer functions may be called
+        // from any context. (The pipe's mutex may be left unlocked, which only causes a later
+        // blocking read to check the buffer once more.)
+        let read =
+            unsafe { riot_sys::tsrb_get(self.pipe.tsrb(), buf.as_mut_ptr(), buf.len() as _) };
+        read as _
+    }
+
+    /// Number of bytes currently in the pipe
+    #[doc(alias = "tsrb_avail")]
+    pub fn available(&self) -> usize {
+        // unsafe: Pipe is initialized by construction; C function only reads
+        unsafe { riot_sys::tsrb_avail(crate::inline_cast(self.pipe.tsrb())) as _ }
+    }
+}
+
+#[cfg(feature = "with_embedded_io")]
+impl<'a, const N: usize> embedded_io::ErrorType for Consumer<'a, N> {
+    type Error = core::convert::Infallible;
+}
+
+/// Blocking read as per [`Consumer::read()`]
+#[cfg(feature = "with_embedded_io")]
+impl<'a, const N: usize> embedded_io::Read for Consumer<'a, N> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        Ok(Consumer::read(self, buf))
+    }
+}
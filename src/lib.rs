@@ -126,6 +126,10 @@ pub mod gnrc_pktbuf;
 pub mod gnrc_util;
 #[cfg(riot_module_periph_i2c)]
 pub mod i2c;
+#[cfg(riot_module_isrpipe)]
+pub mod isrpipe;
+#[cfg(riot_module_core_mbox)]
+pub mod mbox;
 #[cfg(riot_module_core_msg)]
 pub mod msg;
 #[cfg(riot_module_random)]
@@ -143,9 +147,17 @@ pub mod dac;
 #[cfg(riot_module_ztimer)]
 pub mod ztimer;
 
+#[cfg(riot_module_event)]
+pub mod event;
+#[cfg(riot_module_core_thread_flags)]
+pub mod executor;
+
+#[cfg(riot_module_core_cond)]
+pub mod condvar;
 pub mod mutex;
-#[cfg(riot_module_pthread)]
 pub mod rwlock;
+#[cfg(riot_module_sema)]
+pub mod sema;
 
 #[cfg(feature = "set_panic_handler")]
 mod panic;
@@ -83,6 +83,18 @@ impl<F: Fn(StartToken) -> ((), EndToken)> UsableAsMain<[u8; 3]> for F {
     }
 }
 
+#[cfg(riot_module_core_thread_flags)]
+impl<F: Fn() -> Fut, Fut: core::future::Future<Output = T>, T: Termination> Sealed<[u8; 4]> for F {}
+
+#[cfg(riot_module_core_thread_flags)]
+impl<F: Fn() -> Fut, Fut: core::future::Future<Output = T>, T: Termination> UsableAsMain<[u8; 4]>
+    for F
+{
+    unsafe fn call_main(&self) -> i32 {
+        crate::executor::block_on((self)()).report()
+    }
+}
+
 /// To have a nice Rust main function, run the `riot_main!` macro with the name of your main
 /// function an item (ie. top level in a module) in your crate. The function identified by it must
 /// return something that implements the Termination trait.
@@ -112,6 +124,9 @@ impl<F: Fn(StartToken) -> ((), EndToken)> UsableAsMain<[u8; 3]> for F {
 ///   above.
 /// * `fn main(tokens: StartToken) -> !` -- a frequently useful variation thereof for main loops
 ///   that are loops anyway.
+/// * `async fn main() -> impl Termination` -- the future is run on the built-in
+///   [executor](crate::executor) (requires the `core_thread_flags` module). This is convenient
+///   when the application is written against asynchronous APIs such as `embedded-nal-async`.
 #[macro_export]
 macro_rules! riot_main {
     ($main:ident) => {
@@ -0,0 +1,236 @@
+//! Typed bounded queues built on RIOT's [mailboxes](https://doc.riot-os.org/group__core__mbox.html)
+//!
+//! A [Mailbox] is a queue of fixed size that any number of threads can put values into (through a
+//! [Sender]) and take values out of (through a [Receiver]). Unlike the message queue of a
+//! thread (as used in [`msg::v2`](crate::msg::v2)), it is not tied to any thread.
+//!
+//! Values are packed into RIOT messages the same way as in a [ContainerMsg], so they are limited
+//! to the size of a pointer; larger values can be sent by reference.
+//!
+//! ## Example
+//!
+//! ```
+//! # #![no_std]
+//! # #![feature(start)]
+//! # #[start]
+//! # fn main(_argc: isize, _argv: *const *const u8) -> isize { panic!("Doc tests are not supposed to be run") }
+//! # use riot_wrappers::mbox::Mailbox;
+//! let mailbox = core::pin::pin!(Mailbox::<u32, 4>::new());
+//! let (tx, rx) = mailbox.split();
+//! tx.try_put(42).unwrap();
+//! assert!(rx.get() == 42);
+//! ```
+
+use core::cell::UnsafeCell;
+use core::marker::{PhantomData, PhantomPinned};
+use core::pin::Pin;
+
+use riot_sys::msg_t;
+
+use crate::msg::{ContainerMsg, OpaqueMsg, WrapsMsgT};
+
+/// Message type used for all values in a mailbox
+///
+/// As the mailbox is typed, there is no need to discriminate.
+const TYPE: u16 = 0;
+
+/// A bounded queue of `T` values with space for `N` entries
+///
+/// The mailbox is used through the [Sender] and [Receiver] obtained by
+/// [`.split()`](Self::split). Any values still in the mailbox when it is dropped (or split again)
+/// are dropped.
+///
+/// As RIOT keeps a pointer to the mailbox's storage once it is split, the mailbox needs to be
+/// pinned for splitting; [`core::pin::pin!`] does that for a mailbox on the stack, and
+/// [`Pin::static_mut()`] for one in a static.
+///
+/// `N` needs to be a power of 2.
+pub struct Mailbox<T: Send, const N: usize> {
+    mbox: UnsafeCell<riot_sys::mbox_t>,
+    queue: UnsafeCell<[msg_t; N]>,
+    _phantom: PhantomData<T>,
+    _pinned: PhantomPinned,
+}
+
+// unsafe: Values are only moved through the thread and interrupt safe mbox functions
+unsafe impl<T: Send, const N: usize> Send for Mailbox<T, N> {}
+unsafe impl<T: Send, const N: usize> Sync for Mailbox<T, N> {}
+
+impl<T: Send, const N: usize> Mailbox<T, N> {
+    /// Create an empty mailbox
+    pub const fn new() -> Self {
+        const { assert!(N.is_power_of_two(), "Mailbox size needs to be a power of 2") };
+        Mailbox {
+            // unsafe: An all-zero mbox_t is an initialized, empty mailbox without space; it is
+            // given its space when split. An all-zero msg_t is a valid (if meaningless) message.
+            mbox: UnsafeCell::new(unsafe { core::mem::zeroed() }),
+            queue: UnsafeCell::new(unsafe { core::mem::zeroed() }),
+            _phantom: PhantomData,
+            _pinned: PhantomPinned,
+        }
+    }
+
+    /// Obtain the sending and the receiving side of the mailbox
+    ///
+    /// Both can be sent to other threads, and the sender can be cloned.
+    #[doc(alias = "mbox_init")]
+    pub fn split(self: Pin<&mut Self>) -> (Sender<'_, T, N>, Receiver<'_, T, N>) {
+        // Nothing is moved out; from here on, the mailbox is only accessed through its cells.
+        let mailbox = self.into_ref().get_ref();
+        mailbox.drain();
+        // unsafe: No other references to the mailbox exist (as the Pin<&mut> was exclusive), and
+        // the queue is owned by it and pinned along with it, so it stays valid until the mailbox
+        // is dropped. The size is checked at construction time.
+        unsafe {
+            riot_sys::mbox_init(
+                crate::inline_cast_mut(mailbox.mbox.get()),
+                crate::inline_cast_mut(mailbox.queue.get() as *mut msg_t),
+                N as _,
+            )
+        };
+        (Sender { mailbox }, Receiver { mailbox })
+    }
+
+    fn drain(&self) {
+        while self.try_get().is_some() {}
+    }
+
+    fn try_put(&self, value: T) -> Result<(), T> {
+        let mut msg = ContainerMsg::new(TYPE, value).extract();
+        // unsafe: Mailbox is initialized by construction; the non-blocking variant may be called
+        // from any context
+        let result = unsafe {
+            riot_sys::mbox_try_put(
+                crate::inline_cast_mut(self.mbox.get()),
+                crate::inline_cast_mut(&mut msg as *mut msg_t),
+            )
+        };
+        match result {
+            1 => Ok(()),
+            // unsafe: The message was just created from a T
+            _ => Err(unsafe { ContainerMsg::<T>::recognize(OpaqueMsg(msg)) }.get_value()),
+        }
+    }
+
+    fn put(&self, value: T) {
+        crate::thread::InThread::new()
+            .expect("Blocking mailbox operations may only be called outside of interrupt contexts");
+        let mut msg = ContainerMsg::new(TYPE, value).extract();
+        // unsafe: Mailbox is initialized by construction; running in a thread as checked
+        unsafe {
+            riot_sys::mbox_put(
+                crate::inline_cast_mut(self.mbox.get()),
+                crate::inline_cast_mut(&mut msg as *mut msg_t),
+            )
+        };
+    }
+
+    fn try_get(&self) -> Option<T> {
+        let mut msg = msg_t::default();
+        // unsafe: Mailbox is initialized by construction; the non-blocking variant may be called
+        // from any context
+        let result = unsafe {
+            riot_sys::mbox_try_get(
+                crate::inline_cast_mut(self.mbox.get()),
+                crate::inline_cast_mut(&mut msg as *mut msg_t),
+            )
+        };
+        match result {
+            // unsafe: All messages in the mailbox were created from a T
+            1 => Some(unsafe { ContainerMsg::<T>::recognize(OpaqueMsg(msg)) }.get_value()),
+            _ => None,
+        }
+    }
+
+    fn get(&self) -> T {
+        crate::thread::InThread::new()
+            .expect("Blocking mailbox operations may only be called outside of interrupt contexts");
+        let mut msg = msg_t::default();
+        // unsafe: Mailbox is initialized by construction; running in a thread as checked
+        unsafe {
+            riot_sys::mbox_get(
+                crate::inline_cast_mut(self.mbox.get()),
+                crate::inline_cast_mut(&mut msg as *mut msg_t),
+            )
+        };
+        // unsafe: All messages in the mailbox were created from a T
+        unsafe { ContainerMsg::<T>::recognize(OpaqueMsg(msg)) }.get_value()
+    }
+}
+
+impl<T: Send, const N: usize> Default for Mailbox<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Send, const N: usize> Drop for Mailbox<T, N> {
+    fn drop(&mut self) {
+        self.drain();
+    }
+}
+
+/// Sending side of a [Mailbox]
+pub struct Sender<'a, T: Send, const N: usize> {
+    mailbox: &'a Mailbox<T, N>,
+}
+
+impl<'a, T: Send, const N: usize> Sender<'a, T, N> {
+    /// Put a value into the mailbox, blocking while the mailbox is full.
+    ///
+    /// ## Panics
+    ///
+    /// ... when called from an interrupt; use [`.try_put()`](Self::try_put) there.
+    #[doc(alias = "mbox_put")]
+    pub fn put(&self, value: T) {
+        self.mailbox.put(value)
+    }
+
+    /// Put a value into the mailbox if there is space, or return it.
+    ///
+    /// This can be used from interrupts.
+    #[doc(alias = "mbox_try_put")]
+    pub fn try_put(&self, value: T) -> Result<(), T> {
+        self.mailbox.try_put(value)
+    }
+}
+
+impl<'a, T: Send, const N: usize> Clone for Sender<'a, T, N> {
+    fn clone(&self) -> Self {
+        Sender {
+            mailbox: self.mailbox,
+        }
+    }
+}
+
+/// Receiving side of a [Mailbox]
+pub struct Receiver<'a, T: Send, const N: usize> {
+    mailbox: &'a Mailbox<T, N>,
+}
+
+impl<'a, T: Send, const N: usize> Receiver<'a, T, N> {
+    /// Take a value out of the mailbox, blocking until one is available.
+    ///
+    /// ## Panics
+    ///
+    /// ... when called from an interrupt; use [`.try_get()`](Self::try_get) there.
+    #[doc(alias = "mbox_get")]
+    pub fn get(&self) -> T {
+        self.mailbox.get()
+    }
+
+    /// Take a value out of the mailbox if one is available.
+    ///
+    /// This can be used from interrupts.
+    #[doc(alias = "mbox_try_get")]
+    pub fn try_get(&self) -> Option<T> {
+        self.mailbox.try_get()
+    }
+
+    /// Number of values currently in the mailbox
+    #[doc(alias = "mbox_avail")]
+    pub fn available(&self) -> usize {
+        // unsafe: Mailbox is initialized by construction; C function only reads
+        unsafe { riot_sys::mbox_avail(crate::inline_cast_mut(self.mailbox.mbox.get())) as _ }
+    }
+}
@@ -0,0 +1,241 @@
+//! Typed publish/subscribe through RIOT's [message bus](https://doc.riot-os.org/group__core__msg__bus.html)
+//!
+//! A [MsgBus] delivers values of a single type to all threads that subscribed to the value's
+//! topic. Messages arrive as regular messages, whose type number combines the bus ID (in the
+//! upper 11 bits) and the topic (in the lower 5 bits). Subscribing thus takes a
+//! [`msg::v2::SendPort`](super::v2::SendPort) of that type number, which is the receiving
+//! thread's statement that it expects such values there.
+//!
+//! ## Example
+//!
+//! ```
+//! # #![no_std]
+//! # #![feature(start)]
+//! # #[start]
+//! # fn main(_argc: isize, _argv: *const *const u8) -> isize { panic!("Doc tests are not supposed to be run") }
+//! # use riot_wrappers::msg::v2::*;
+//! # use riot_wrappers::msg::bus::*;
+//! # use riot_wrappers::thread::StartToken;
+//! static NETWORK: MsgBus<u32, 7> = MsgBus::new();
+//! const NETWORK_UP: u16 = MsgBus::<u32, 7>::typeno(0);
+//!
+//! fn thread(tok: StartToken) -> ! {
+//!     tok.with_message_queue::<4, _>(|tok| {
+//!         let (_tok, semantics) = tok.take_msg_semantics();
+//!         let (semantics, network_up, port): (_, ReceivePort<u32, NETWORK_UP>, _) = semantics.split_off();
+//!         let mut slot = core::pin::pin!(SubscriptionSlot::default());
+//!         let _subscription = NETWORK.subscribe(port, slot.as_mut());
+//!
+//!         NETWORK.publish::<NETWORK_UP>(1);
+//!
+//!         loop {
+//!             semantics
+//!                 .receive()
+//!                 .decode(&network_up, |_, interface| riot_wrappers::println!("Network {} is up", interface))
+//!                 .expect("No other messages are configured");
+//!         }
+//!     })
+//! }
+//! ```
+
+use core::cell::UnsafeCell;
+use core::marker::{PhantomData, PhantomPinned};
+use core::pin::Pin;
+
+use super::v2::SendPort;
+
+/// A message bus carrying values of type `T`, identified by `ID`
+///
+/// The bus is typically placed in a static. Its ID (0 to 2047) is set at build time, so that
+/// receivers can set up their ports for its type numbers. It must not collide with the ID of any
+/// other bus in the system, including those set up by C code through `msg_bus_init`, which assigns
+/// IDs automatically.
+///
+/// As every subscriber receives its own copy of each published value, `T` needs to be [Copy];
+/// like all message content, it needs to fit in a pointer.
+pub struct MsgBus<T: Copy + Send, const ID: u16> {
+    bus: UnsafeCell<riot_sys::msg_bus_t>,
+    _phantom: PhantomData<T>,
+}
+
+// unsafe: The bus is only accessed through RIOT functions that handle concurrent access
+unsafe impl<T: Copy + Send, const ID: u16> Send for MsgBus<T, ID> {}
+unsafe impl<T: Copy + Send, const ID: u16> Sync for MsgBus<T, ID> {}
+
+impl<T: Copy + Send, const ID: u16> MsgBus<T, ID> {
+    /// Create a message bus without subscribers
+    pub const fn new() -> Self {
+        const { assert!(ID < (1 << 11), "Message bus IDs are limited to 11 bits") };
+        // unsafe: All-zero is an empty subscriber list (RIOT guards it by disabling interrupts)
+        let mut bus: riot_sys::msg_bus_t = unsafe { core::mem::zeroed() };
+        // This is what msg_bus_init does, but with our chosen ID
+        bus.id = ID;
+        MsgBus {
+            bus: UnsafeCell::new(bus),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Message type number under which values published on `topic` (0 to 31) are received
+    pub const fn typeno(topic: u8) -> u16 {
+        assert!(topic < 32, "Message bus topics are limited to 5 bits");
+        (ID << 5) | topic as u16
+    }
+
+    /// Send a value to all threads that subscribed to the topic indicated by its type number.
+    ///
+    /// This can be used both from threads and from interrupts. Subscribers whose message queues
+    /// are full miss the value.
+    ///
+    /// Returns the number of threads the value was delivered to.
+    #[doc(alias = "msg_send_bus")]
+    pub fn publish<const TYPENO: u16>(&self, value: T) -> usize {
+        const {
+            assert!(
+                TYPENO >> 5 == ID,
+                "Type number does not belong to this message bus"
+            )
+        };
+        super::v2::assert_transportable::<T>();
+
+        let mut arg: *const crate::libc::c_void = core::ptr::null();
+        // unsafe: Size and alignment were checked above; the value is Copy, so no drop is missed
+        unsafe { core::ptr::write(&mut arg as *mut _ as *mut T, value) };
+        // unsafe: Bus is initialized by construction; the function may be called from any context
+        let sent = unsafe {
+            riot_sys::msg_send_bus(crate::inline_cast_mut(self.bus.get()), TYPENO & 0x1f, arg)
+        };
+        sent as _
+    }
+
+    /// Subscribe the current thread to a topic
+    ///
+    /// The topic is given by the type number of the send port, which is consumed for as long as
+    /// the subscription lasts. The subscription is kept in the pinned slot, and removed when the
+    /// returned [Subscription] (or the slot, if the subscription is leaked) is dropped.
+    ///
+    /// ## Panics
+    ///
+    /// ... if the port does not lead to the current thread.
+    #[doc(alias = "msg_bus_attach")]
+    #[doc(alias = "msg_bus_subscribe")]
+    pub fn subscribe<'a, const TYPENO: u16>(
+        &'static self,
+        port: SendPort<T, TYPENO>,
+        mut slot: Pin<&'a mut SubscriptionSlot>,
+    ) -> Subscription<'a, T, TYPENO> {
+        const {
+            assert!(
+                TYPENO >> 5 == ID,
+                "Type number does not belong to this message bus"
+            )
+        };
+        // msg_bus_attach always subscribes the current thread
+        assert!(
+            port.destination() == crate::thread::get_pid(),
+            "Message bus subscriptions can only be made from the receiving thread"
+        );
+
+        slot.as_mut().detach();
+        let entry = slot.entry.get();
+        // unsafe: The bus is initialized by construction and lives forever; the entry is pinned,
+        // and detached from any bus before the slot goes away.
+        unsafe {
+            riot_sys::msg_bus_attach(
+                crate::inline_cast_mut(self.bus.get()),
+                crate::inline_cast_mut(entry),
+            );
+            riot_sys::msg_bus_subscribe(crate::inline_cast_mut(entry), (TYPENO & 0x1f) as u8);
+        }
+        // unsafe: The bus pointer is not structurally pinned
+        unsafe { slot.as_mut().get_unchecked_mut() }.bus = self.bus.get();
+
+        Subscription { slot, port }
+    }
+}
+
+impl<T: Copy + Send, const ID: u16> Default for MsgBus<T, ID> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pinned memory that holds a thread's registration with a [MsgBus]
+///
+/// A simple `core::pin::pin!(SubscriptionSlot::default())` gives a slot suitable for
+/// [`MsgBus::subscribe()`].
+pub struct SubscriptionSlot {
+    entry: UnsafeCell<riot_sys::msg_bus_entry_t>,
+    // The entry does not record which bus it is on; if this is not null, the entry is attached to
+    // that bus.
+    bus: *mut riot_sys::msg_bus_t,
+    _phantom: PhantomPinned,
+}
+
+impl SubscriptionSlot {
+    fn detach(self: Pin<&mut Self>) {
+        // unsafe: The bus pointer is not structurally pinned, and the entry is not moved
+        let this = unsafe { self.get_unchecked_mut() };
+        if !this.bus.is_null() {
+            // unsafe: The entry was attached to that bus, which is static
+            unsafe {
+                riot_sys::msg_bus_detach(
+                    crate::inline_cast_mut(this.bus),
+                    crate::inline_cast_mut(this.entry.get()),
+                )
+            };
+            // Detached, so RIOT does not look at the entry any more
+            this.bus = core::ptr::null_mut();
+        }
+    }
+}
+
+impl Default for SubscriptionSlot {
+    fn default() -> Self {
+        SubscriptionSlot {
+            // unsafe: All-zero is an unattached entry
+            entry: UnsafeCell::new(unsafe { core::mem::zeroed() }),
+            bus: core::ptr::null_mut(),
+            _phantom: PhantomPinned,
+        }
+    }
+}
+
+impl Drop for SubscriptionSlot {
+    fn drop(&mut self) {
+        // unsafe: Drop is only ever called on the pinned (or never pinned) slot
+        unsafe { Pin::new_unchecked(self) }.detach();
+    }
+}
+
+/// A thread's subscription to a topic on a [MsgBus]
+///
+/// Dropping the subscription ends it; messages that were already sent remain in the thread's
+/// queue, and are decoded through the receive port as usual.
+pub struct Subscription<'a, T: Send, const TYPENO: u16> {
+    slot: Pin<&'a mut SubscriptionSlot>,
+    port: SendPort<T, TYPENO>,
+}
+
+impl<'a, T: Send, const TYPENO: u16> Subscription<'a, T, TYPENO> {
+    /// End the subscription, and give back the port that was used to subscribe.
+    #[doc(alias = "msg_bus_detach")]
+    pub fn unsubscribe(self) -> SendPort<T, TYPENO> {
+        let mut this = core::mem::ManuallyDrop::new(self);
+        this.slot.as_mut().detach();
+        // unsafe: The port is moved out exactly once, and self is not dropped
+        unsafe { core::ptr::read(&this.port) }
+    }
+}
+
+impl<'a, T: Send, const TYPENO: u16> Drop for Subscription<'a, T, TYPENO> {
+    fn drop(&mut self) {
+        self.slot.as_mut().detach();
+    }
+}
+
+impl<'a, T: Send, const TYPENO: u16> core::fmt::Debug for Subscription<'a, T, TYPENO> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        write!(f, "Subscription {{ port: {:?} }}", self.port)
+    }
+}
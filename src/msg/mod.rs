@@ -15,6 +15,9 @@ use riot_sys::{self, kernel_pid_t, msg_receive, msg_reply, msg_send, msg_send_re
 #[cfg(feature = "with_msg_v2")]
 pub mod v2;
 
+#[cfg(all(feature = "with_msg_v2", riot_module_core_msg_bus))]
+pub mod bus;
+
 /// The source of a message
 // Ideally this would be represented in memory 1:1 like a KernelPID, but I can't tell Rust that a
 // KernelPID has a valid range from KERNEL_PID_FIRST to KERNEL_PID_LAST and have it use that
@@ -98,7 +101,7 @@ where
 }
 
 /// An initialized message with inaccessible value.
-pub struct OpaqueMsg(msg_t);
+pub struct OpaqueMsg(pub(crate) msg_t);
 
 impl OpaqueMsg {
     pub fn receive() -> OpaqueMsg {
@@ -204,14 +207,15 @@ where
                 "Type too large to send"
             )
         };
+        let mut message = msg_t {
+            type_,
+            ..msg_t::default()
+        };
+        // unsafe: Size was checked above. The value is moved into the message (and thus not
+        // dropped here); it is only moved out again in get_value.
+        unsafe { core::ptr::write_unaligned(&mut message.content as *mut _ as *mut T, value) };
         ContainerMsg {
-            message: msg_t {
-                type_,
-                content: riot_sys::msg_t__bindgen_ty_1 {
-                    ptr: unsafe { ::core::mem::transmute_copy(&value) },
-                },
-                ..msg_t::default()
-            },
+            message,
             t: PhantomData,
         }
     }
@@ -227,3 +231,12 @@ where
         }
     }
 }
+
+impl<T> WrapsMsgT for ContainerMsg<T> {
+    fn extract(self) -> msg_t {
+        self.message
+    }
+    fn view(&self) -> &msg_t {
+        &self.message
+    }
+}
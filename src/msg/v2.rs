@@ -3,7 +3,12 @@
 //! This module's main contribution is [MessageSemantics], a chaining family of ZSTs that
 //! represent which types a thread expects to come in on which message numbers. From that, pairs of
 //! [SendPort]s (through which other threads send messages) and [ReceivePort]s (using which
-//! received messages are decoded) are split off.
+//! received messages are decoded) are split off. For requests that the receiving thread answers,
+//! pairs of [RpcCallPort]s and [RpcPort]s are split off the same way.
+//!
+//! With ztimer, values can be sent through a port when a timer expires using a
+//! [ScheduledMessage], and messages can be received with a timeout through
+//! [`MessageSemantics::receive_timeout()`].
 //!
 //! For safety, the module relies on other components not tossing around messages indiscriminately.
 //! In Rust, senders are told through the SendPort how the recipient will transmute the data back.
@@ -94,6 +99,31 @@ impl<TYPE: Send, const TYPENO: u16> SendPort<TYPE, TYPENO> {
     /// error can not happen here. (If it still does due to errors in unsafe code, trips up a debug
     /// assert and else is handled like the other failure to send).
     pub fn try_send(&self, data: TYPE) -> Result<(), TYPE> {
+        // unsafe: msg_try_send only needs a valid message and PID
+        self.send_with(data, |msg, pid| unsafe { riot_sys::msg_try_send(msg, pid) })
+    }
+
+    /// Send a message to a given ticket from an interrupt.
+    ///
+    /// This behaves like [`.try_send()`](Self::try_send), but is safe to use in interrupt
+    /// context, as witnessed by the `InIsr` token. The receiving thread will see the message as
+    /// coming from an ISR.
+    ///
+    /// As messages that do not fit in the recipient's queue are returned, interrupt producers can
+    /// use [`.queued()`](Self::queued) and [`.queue_capacity()`](Self::queue_capacity) to apply
+    /// back-pressure early.
+    #[doc(alias = "msg_send_int")]
+    pub fn try_send_isr(&self, data: TYPE, _isr: thread::InIsr) -> Result<(), TYPE> {
+        // unsafe: msg_send_int only needs a valid message and PID, and to be called from an
+        // interrupt, as witnessed by the token
+        self.send_with(data, |msg, pid| unsafe { riot_sys::msg_send_int(msg, pid) })
+    }
+
+    fn send_with(
+        &self,
+        data: TYPE,
+        send: impl FnOnce(*mut riot_sys::msg_t, riot_sys::kernel_pid_t) -> i32,
+    ) -> Result<(), TYPE> {
         let mut msg: riot_sys::msg_t = Default::default();
         msg.type_ = TYPENO;
 
@@ -103,7 +133,7 @@ impl<TYPE: Send, const TYPENO: u16> SendPort<TYPE, TYPENO> {
             core::mem::transmute(&mut msg.content)
         });
 
-        let result = unsafe { riot_sys::msg_try_send(&mut msg, self.destination.into()) };
+        let result = send(&mut msg, self.destination.into());
         // Outside debug, behaves like the thread isn't ready, which is quite accurate for an
         // invalid one.
         debug_assert!(
@@ -122,6 +152,28 @@ impl<TYPE: Send, const TYPENO: u16> SendPort<TYPE, TYPENO> {
         }
     }
 
+    /// Number of messages currently waiting in the destination thread's message queue
+    ///
+    /// This counts all messages, not only those sent through this port. It is 0 if the thread
+    /// has no message queue.
+    #[doc(alias = "msg_avail_thread")]
+    pub fn queued(&self) -> usize {
+        // unsafe: C function only reads, and can be called from any context
+        let queued = unsafe { riot_sys::msg_avail_thread(self.destination.into()) };
+        queued.try_into().unwrap_or(0)
+    }
+
+    /// Number of messages that fit in the destination thread's message queue
+    ///
+    /// This is 0 if the thread has no message queue, in which case messages can only be sent
+    /// successfully while the thread is blocked waiting for one.
+    #[doc(alias = "msg_queue_capacity")]
+    pub fn queue_capacity(&self) -> usize {
+        // unsafe: C function only reads, and can be called from any context
+        let capacity = unsafe { riot_sys::msg_queue_capacity(self.destination.into()) };
+        capacity.try_into().unwrap_or(0)
+    }
+
     /// Access the port's destination
     ///
     /// This is particularly useful when messages are not sent directly through [`SendPort::try_send()`], but
@@ -156,6 +208,335 @@ impl<TYPE: Send, const TYPENO: u16> core::fmt::Debug for SendPort<TYPE, TYPENO>
     }
 }
 
+/// A value that is sent through a [SendPort] when a timer expires
+///
+/// Once [scheduled](Self::schedule), the value arrives at the port's thread as a regular message
+/// (sent from interrupt context), and is decoded through the corresponding [ReceivePort]. This is
+/// useful for timers such as retransmissions in protocol state machines, which are then handled
+/// in the same place as all other events.
+///
+/// If the destination's message queue is full when the timer expires, the value is lost (without
+/// being dropped).
+///
+/// ```
+/// # #![no_std]
+/// # #![feature(start)]
+/// # #[start]
+/// # fn main(_argc: isize, _argv: *const *const u8) -> isize { panic!("Doc tests are not supposed to be run") }
+/// # use riot_wrappers::msg::v2::*;
+/// # use riot_wrappers::thread::StartToken;
+/// # use riot_wrappers::ztimer::{Clock, Ticks};
+/// fn thread(tok: StartToken) -> ! {
+///     // The message is sent from interrupt context, so it needs a queue to arrive in
+///     tok.with_message_queue::<4, _>(|tok| {
+///         let (_tok, semantics) = tok.take_msg_semantics();
+///         let (semantics, retransmit, retransmit_port): (_, ReceivePort<u8, 1>, _) = semantics.split_off();
+///         let mut timer = core::pin::pin!(ScheduledMessage::new(&retransmit_port, Clock::msec_unbound()));
+///         timer.as_mut().schedule(1, Ticks(500));
+///
+///         loop {
+///             match semantics.receive_timeout(&Clock::msec_unbound(), Ticks(1000)) {
+///                 Some(message) => {
+///                     message.decode(&retransmit, |_, attempt| riot_wrappers::println!("Retransmitting ({})", attempt));
+///                 }
+///                 None => riot_wrappers::println!("Nothing happened"),
+///             }
+///         }
+///     })
+/// }
+/// ```
+#[cfg(riot_module_ztimer)]
+pub struct ScheduledMessage<'a, TYPE: Send, const TYPENO: u16, const HZ: u32> {
+    port: &'a SendPort<TYPE, TYPENO>,
+    clock: crate::ztimer::Clock<HZ>,
+    // While the timer is set, it points to the message, and the message holds a TYPE.
+    timer: core::cell::UnsafeCell<riot_sys::ztimer_t>,
+    msg: core::cell::UnsafeCell<riot_sys::msg_t>,
+    _phantom: core::marker::PhantomPinned,
+}
+
+#[cfg(riot_module_ztimer)]
+impl<'a, TYPE: Send, const TYPENO: u16, const HZ: u32> ScheduledMessage<'a, TYPE, TYPENO, HZ> {
+    /// Create a timer for values to be sent through the port after some time on the clock
+    pub fn new(port: &'a SendPort<TYPE, TYPENO>, clock: crate::ztimer::Clock<HZ>) -> Self {
+        assert_transportable::<TYPE>();
+        ScheduledMessage {
+            port,
+            clock,
+            timer: core::cell::UnsafeCell::new(Default::default()),
+            msg: core::cell::UnsafeCell::new(Default::default()),
+            _phantom: core::marker::PhantomPinned,
+        }
+    }
+
+    /// Send the value after the given number of ticks.
+    ///
+    /// If a value was already scheduled and has not been sent yet, it is not sent any more but
+    /// returned.
+    #[doc(alias = "ztimer_set_msg")]
+    pub fn schedule(
+        mut self: core::pin::Pin<&mut Self>,
+        value: TYPE,
+        delay: crate::ztimer::Ticks<HZ>,
+    ) -> Option<TYPE> {
+        let previous = self.as_mut().cancel();
+        // unsafe: The timer is not set, so nothing else accesses the message.
+        unsafe { *self.msg.get() = pack(TYPENO, value) };
+        // unsafe: Timer and message stay valid as self is pinned, and the timer is removed before
+        // self goes away.
+        unsafe {
+            riot_sys::ztimer_set_msg(
+                self.clock.0,
+                self.timer.get(),
+                delay.0,
+                self.msg.get(),
+                self.port.destination.into(),
+            )
+        };
+        previous
+    }
+
+    /// Stop the timer, returning the scheduled value if it has not been sent yet.
+    #[doc(alias = "ztimer_remove")]
+    pub fn cancel(self: core::pin::Pin<&mut Self>) -> Option<TYPE> {
+        // unsafe: OK per C API; removing works no matter whether the timer is set
+        let removed = unsafe { riot_sys::ztimer_remove(self.clock.0, self.timer.get()) };
+        if removed {
+            // unsafe: The message was packed from a TYPE in schedule(), and as the timer was still
+            // set, the value was not sent; as the timer is removed, it is taken out exactly once.
+            Some(unsafe { core::ptr::read(&(*self.msg.get()).content as *const _ as *const TYPE) })
+        } else {
+            None
+        }
+    }
+
+    /// Whether a value is scheduled and has not been sent yet
+    #[doc(alias = "ztimer_is_set")]
+    pub fn is_scheduled(&self) -> bool {
+        // unsafe: C function only reads
+        unsafe { riot_sys::ztimer_is_set(self.clock.0, self.timer.get()) != 0 }
+    }
+}
+
+#[cfg(riot_module_ztimer)]
+impl<'a, TYPE: Send, const TYPENO: u16, const HZ: u32> Drop
+    for ScheduledMessage<'a, TYPE, TYPENO, HZ>
+{
+    fn drop(&mut self) {
+        // unsafe: Drop is only ever called on the pinned (or never pinned) value
+        drop(unsafe { core::pin::Pin::new_unchecked(self) }.cancel());
+    }
+}
+
+#[cfg(riot_module_ztimer)]
+impl<'a, TYPE: Send, const TYPENO: u16, const HZ: u32> core::fmt::Debug
+    for ScheduledMessage<'a, TYPE, TYPENO, HZ>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        write!(
+            f,
+            "ScheduledMessage {{ port: {:?}, scheduled: {} }}",
+            self.port,
+            self.is_scheduled()
+        )
+    }
+}
+
+/// Build-time check that a type can be moved into and out of a message's content
+pub(super) fn assert_transportable<T>() {
+    const {
+        assert!(
+            core::mem::size_of::<T>() <= core::mem::size_of::<riot_sys::msg_t__bindgen_ty_1>(),
+            "Type is too large to be transported in a message"
+        )
+    };
+
+    // ... and the alignment must suffice because the data is moved in and outthrough a &mut
+    // SomethingTransparent<T>
+    const {
+        assert!(
+            core::mem::align_of::<T>() <= core::mem::align_of::<riot_sys::msg_t__bindgen_ty_1>(),
+            "Type has stricter alignment requirements than the message content"
+        )
+    };
+}
+
+/// Create a message of the given type number that carries the data
+///
+/// The data needs to have passed [assert_transportable].
+fn pack<T>(typeno: u16, data: T) -> riot_sys::msg_t {
+    let mut msg: riot_sys::msg_t = Default::default();
+    msg.type_ = typeno;
+    // unsafe: Size and alignment were checked when the port was created
+    unsafe { core::ptr::write(&mut msg.content as *mut _ as *mut T, data) };
+    msg
+}
+
+/// Thread-bound information carrier that indicates that a given type number was reserved for
+/// requests of type `Req` that are answered with a `Resp`.
+///
+/// This is the RPC counterpart of a [ReceivePort]; requests are decoded using
+/// [`ReceivedMessage::decode_rpc()`].
+pub struct RpcPort<Req: Send, Resp: Send, const TYPENO: u16> {
+    // Can only be constructed by split_off_rpc()
+    _private: (),
+    _types: PhantomData<(Req, Resp)>,
+    // Not Send or Sync for the same reasons as ReceivePort
+    _not_send: PhantomData<*const ()>,
+}
+
+/// Type number of the reply that is sent to an RPC caller when its request is dropped without
+/// being answered
+///
+/// This type number can not be used by [RpcPort]s.
+pub const RPC_UNANSWERED_TYPENO: u16 = 0xffff;
+
+/// Object through which requests of a precise type can be sent to a precise thread, blocking
+/// until they are answered.
+///
+/// Like [SendPort], it is Send and Sync, and can be used through shared references.
+pub struct RpcCallPort<Req: Send, Resp: Send, const TYPENO: u16> {
+    destination: thread::KernelPID,
+    // Only Req values are moved out of here, and only Resp values are produced here, both of
+    // which are Send; unlike a PhantomData<(Req, Resp)>, this does not require them to be Sync
+    // for the port to be Sync.
+    _phantom: PhantomData<fn(Req) -> Resp>,
+}
+
+impl<Req: Send, Resp: Send, const TYPENO: u16> RpcCallPort<Req, Resp, TYPENO> {
+    /// Send a request and block until it is answered.
+    ///
+    /// This returns None if the receiving thread dropped the request without answering it, either
+    /// by dropping its [ReplyHandle] or by not decoding the request in the first place.
+    ///
+    /// # Panics
+    ///
+    /// ... when called from an interrupt, as this needs to block.
+    ///
+    /// Calling this from the thread that is supposed to answer the request is an error (and trips
+    /// an assertion in RIOT if those are enabled).
+    #[doc(alias = "msg_send_receive")]
+    pub fn call(&self, request: Req) -> Option<Resp> {
+        crate::thread::InThread::new()
+            .expect("RPC calls may only be made outside of interrupt contexts");
+
+        let mut msg = pack(TYPENO, request);
+        let mut reply: riot_sys::msg_t = Default::default();
+        // unsafe: Both messages are valid for the duration of the call, which is made in a thread
+        let result =
+            unsafe { riot_sys::msg_send_receive(&mut msg, &mut reply, self.destination.into()) };
+        // See try_send on why this can't happen
+        debug_assert!(
+            result == 1,
+            "Target PID vanished even though an RpcCallPort was still around"
+        );
+        if reply.type_ == TYPENO {
+            // unsafe: Replies of this type number are only created by ReplyHandle::reply, which
+            // packs a Resp
+            Some(unsafe { core::ptr::read(&reply.content as *const _ as *const Resp) })
+        } else {
+            debug_assert!(
+                reply.type_ == RPC_UNANSWERED_TYPENO,
+                "RPC call received a reply of unexpected type"
+            );
+            None
+        }
+    }
+
+    /// Access the port's destination
+    pub fn destination(&self) -> thread::KernelPID {
+        self.destination
+    }
+}
+
+/// Obligation to answer a request received on an [RpcPort]
+///
+/// The caller is blocked until [`.reply()`](ReplyHandle::reply) is called. Dropping the handle
+/// without replying unblocks the caller, whose [`.call()`](RpcCallPort::call) then returns None.
+#[must_use = "The caller stays blocked until a reply is sent"]
+pub struct ReplyHandle<Resp: Send, const TYPENO: u16> {
+    caller: riot_sys::kernel_pid_t,
+    _phantom: PhantomData<Resp>,
+}
+
+impl<Resp: Send, const TYPENO: u16> ReplyHandle<Resp, TYPENO> {
+    /// Send the response to the blocked caller.
+    ///
+    /// This can be done from any thread.
+    #[doc(alias = "msg_reply")]
+    pub fn reply(self, response: Resp) {
+        send_reply(self.caller, pack(TYPENO, response));
+        core::mem::forget(self);
+    }
+}
+
+impl<Resp: Send, const TYPENO: u16> Drop for ReplyHandle<Resp, TYPENO> {
+    fn drop(&mut self) {
+        send_unanswered(self.caller);
+    }
+}
+
+/// Unblock an RPC caller with the given reply
+fn send_reply(caller: riot_sys::kernel_pid_t, mut reply: riot_sys::msg_t) {
+    let mut request = riot_sys::msg_t {
+        sender_pid: caller,
+        ..Default::default()
+    };
+    // unsafe: Both messages are valid for the duration of the call, and only the request's
+    // sender is read
+    let result = unsafe { riot_sys::msg_reply(&mut request, &mut reply) };
+    // The caller can not do anything but wait for the reply
+    debug_assert!(result == 1, "RPC caller was not waiting for a reply");
+}
+
+/// Unblock an RPC caller whose request was dropped without being answered
+fn send_unanswered(caller: riot_sys::kernel_pid_t) {
+    send_reply(
+        caller,
+        riot_sys::msg_t {
+            type_: RPC_UNANSWERED_TYPENO,
+            ..Default::default()
+        },
+    );
+}
+
+impl<Req: Send, Resp: Send, const TYPENO: u16> core::fmt::Debug for RpcPort<Req, Resp, TYPENO> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        write!(
+            f,
+            "RpcPort<{}, {}, {}> {{ }}",
+            core::any::type_name::<Req>(),
+            core::any::type_name::<Resp>(),
+            TYPENO,
+        )
+    }
+}
+
+impl<Req: Send, Resp: Send, const TYPENO: u16> core::fmt::Debug for RpcCallPort<Req, Resp, TYPENO> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        write!(
+            f,
+            "RpcCallPort<{}, {}, {}> {{ destination: {:?} }}",
+            core::any::type_name::<Req>(),
+            core::any::type_name::<Resp>(),
+            TYPENO,
+            self.destination
+        )
+    }
+}
+
+impl<Resp: Send, const TYPENO: u16> core::fmt::Debug for ReplyHandle<Resp, TYPENO> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        write!(
+            f,
+            "ReplyHandle<{}, {}> {{ caller: {:?} }}",
+            core::any::type_name::<Resp>(),
+            TYPENO,
+            self.caller
+        )
+    }
+}
+
 /// Trait for types that indicate the current thread's readiness to receive some set of messages
 ///
 /// In a sense, a MessageSemantics is factory for mutually nonconflicting [ReceivePort]s, and a
@@ -205,23 +586,7 @@ pub trait MessageSemantics: Sized {
             "Type number is already in use for this thread."
         );
 
-        const {
-            assert!(
-                core::mem::size_of::<NewType>()
-                    <= core::mem::size_of::<riot_sys::msg_t__bindgen_ty_1>(),
-                "Type is too large to be transported in a message"
-            )
-        };
-
-        // ... and the alignment must suffice because the data is moved in and outthrough a &mut
-        // SomethingTransparent<T>
-        const {
-            assert!(
-                core::mem::align_of::<NewType>()
-                    <= core::mem::align_of::<riot_sys::msg_t__bindgen_ty_1>(),
-                "Type has stricter alignment requirements than the message content"
-            )
-        };
+        assert_transportable::<NewType>();
 
         (
             Processing {
@@ -240,6 +605,54 @@ pub trait MessageSemantics: Sized {
         )
     }
 
+    /// Reduce the type into a new MessageSemantics that knows about one more typeno on which
+    /// requests of type `Req` are received and answered with a `Resp`, along with an [RpcPort] to
+    /// decode the requests and an [RpcCallPort] through which other threads send them.
+    ///
+    /// Requests that are received but not decoded through the RpcPort are dropped, and their
+    /// callers are told that the request was not answered.
+    ///
+    /// # Panics
+    ///
+    /// ... under the same conditions as [`.split_off()`](MessageSemantics::split_off), where
+    /// both the request and the response type need to fit in a message, and additionally if the
+    /// type number is [RPC_UNANSWERED_TYPENO].
+    fn split_off_rpc<Req: Send, Resp: Send, const NEW_TYPENO: u16>(
+        self,
+    ) -> (
+        ProcessingRpc<Self, Req, NEW_TYPENO>,
+        RpcPort<Req, Resp, NEW_TYPENO>,
+        RpcCallPort<Req, Resp, NEW_TYPENO>,
+    ) {
+        assert!(
+            !self.typeno_is_known(NEW_TYPENO),
+            "Type number is already in use for this thread."
+        );
+        assert!(
+            NEW_TYPENO != RPC_UNANSWERED_TYPENO,
+            "Type number is reserved for unanswered RPC replies."
+        );
+
+        assert_transportable::<Req>();
+        assert_transportable::<Resp>();
+
+        (
+            ProcessingRpc {
+                tail: self,
+                _type: PhantomData,
+            },
+            RpcPort {
+                _private: (),
+                _types: PhantomData,
+                _not_send: PhantomData,
+            },
+            RpcCallPort {
+                destination: thread::get_pid(),
+                _phantom: PhantomData,
+            },
+        )
+    }
+
     /// Block to receive a single message
     // No override should be necessary for this, not even for internal impls (see sealing above)
     #[doc(alias = "msg_receive")]
@@ -269,6 +682,79 @@ pub trait MessageSemantics: Sized {
         }
     }
 
+    /// Block to receive a single message, giving up after the timeout on the given clock
+    ///
+    /// Returns None if no message arrived in time.
+    ///
+    /// The timeout is delivered as a message of type `MSG_ZTIMER`, like in
+    /// `ztimer_msg_receive_timeout`. If the timeout expires just as a message arrives, the
+    /// timeout's message may stay in the queue; later calls to this function recognize and discard
+    /// it, but other receive functions pass it on like any other message.
+    #[cfg(riot_module_ztimer)]
+    #[doc(alias = "ztimer_msg_receive_timeout")]
+    fn receive_timeout<const HZ: u32>(
+        &self,
+        clock: &crate::ztimer::Clock<HZ>,
+        timeout: crate::ztimer::Ticks<HZ>,
+    ) -> Option<ReceivedMessage<'_, Self>> {
+        use core::sync::atomic::{AtomicBool, Ordering};
+
+        // Whether this call's timer has fired; timeout messages received before that were left
+        // over from earlier calls.
+        let fired = AtomicBool::new(false);
+        let pid: riot_sys::kernel_pid_t = thread::get_pid().into();
+        let msg = clock.set_during(
+            || {
+                fired.store(true, Ordering::Relaxed);
+                let mut message = timeout_message();
+                // unsafe: C function can be called from interrupts, and only reads the message.
+                // It can only fail when the thread is not waiting any more, and then nothing is
+                // lost.
+                unsafe { riot_sys::msg_send_int(&mut message, pid) };
+            },
+            timeout,
+            || loop {
+                let mut msg = MaybeUninit::uninit();
+                // unsafe: Called from the thread the semantics are bound to
+                unsafe { riot_sys::msg_receive(msg.as_mut_ptr()) };
+                let msg = unsafe { msg.assume_init() };
+                if !is_timeout_message(&msg) {
+                    break Some(msg);
+                }
+                if fired.load(Ordering::Relaxed) {
+                    break None;
+                }
+                // The timer keeps running, so waiting goes on for the remaining time.
+            },
+        )?;
+        Some(ReceivedMessage {
+            msg,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Wait for a single message inside an async executor
+    ///
+    /// The returned future resolves to the next message. As RIOT has no means of waking a waker
+    /// when a message arrives, the future relies on the executor to poll it again when the
+    /// thread's `THREAD_FLAG_MSG_WAITING` flag is set. That flag is only set when a message is put
+    /// into the thread's message queue, so the thread needs to have one (see
+    /// [`TokenParts::with_message_queue()`](crate::thread::TokenParts::with_message_queue)).
+    ///
+    /// This allows awaiting messages concurrently with other futures, such as timers or sockets.
+    ///
+    /// Only [`executor::block_on()`](crate::executor::block_on) running in the current thread
+    /// lets the thread sleep until a message arrives. Any other executor on the current thread is
+    /// woken again whenever the future is pending, which keeps it polling (and the thread busy)
+    /// until a message arrives.
+    #[cfg(riot_module_core_thread_flags)]
+    fn receive_async(&self) -> ReceiveFuture<'_, Self> {
+        ReceiveFuture {
+            semantics: self,
+            _not_send: PhantomData,
+        }
+    }
+
     /// Interpret a message according to these semantics, then drop it.
     ///
     ///
@@ -286,6 +772,70 @@ pub trait MessageSemantics: Sized {
     unsafe fn drop(message: &mut ReceivedMessage<'_, Self>);
 }
 
+/// Marker whose address identifies the messages through which
+/// [`.receive_timeout()`](MessageSemantics::receive_timeout) learns of its timeout
+#[cfg(riot_module_ztimer)]
+static TIMEOUT_MARKER: u8 = 0;
+
+#[cfg(riot_module_ztimer)]
+fn timeout_message() -> riot_sys::msg_t {
+    riot_sys::msg_t {
+        type_: riot_sys::MSG_ZTIMER as u16,
+        content: riot_sys::msg_t__bindgen_ty_1 {
+            ptr: &TIMEOUT_MARKER as *const u8 as *mut _,
+        },
+        ..Default::default()
+    }
+}
+
+#[cfg(riot_module_ztimer)]
+fn is_timeout_message(msg: &riot_sys::msg_t) -> bool {
+    // unsafe: Reading the content as a pointer is what ztimer does for its own timeout messages
+    // too; the value is only compared.
+    msg.type_ == riot_sys::MSG_ZTIMER as u16
+        && unsafe { msg.content.ptr } == &TIMEOUT_MARKER as *const u8 as *mut _
+}
+
+/// Future returned by [`MessageSemantics::receive_async()`]
+#[cfg(riot_module_core_thread_flags)]
+#[must_use = "futures do nothing unless awaited"]
+pub struct ReceiveFuture<'a, S: MessageSemantics> {
+    semantics: &'a S,
+    // Messages can only be received in the thread they were sent to
+    _not_send: PhantomData<*const ()>,
+}
+
+#[cfg(riot_module_core_thread_flags)]
+impl<'a, S: MessageSemantics> core::future::Future for ReceiveFuture<'a, S> {
+    type Output = ReceivedMessage<'a, S>;
+
+    fn poll(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Self::Output> {
+        let semantics: &'a S = self.semantics;
+        match semantics.try_receive() {
+            Some(message) => core::task::Poll::Ready(message),
+            None => {
+                // Any message arriving after this sets THREAD_FLAG_MSG_WAITING, on which block_on
+                // polls again. Other executors would never get to know, so they are asked to poll
+                // again right away.
+                if !crate::executor::is_current_executor(cx.waker()) {
+                    cx.waker().wake_by_ref();
+                }
+                core::task::Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(riot_module_core_thread_flags)]
+impl<'a, S: MessageSemantics> core::fmt::Debug for ReceiveFuture<'a, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        write!(f, "ReceiveFuture {{ }}")
+    }
+}
+
 pub struct NoConfiguredMessages;
 
 /// The MessageSemantics of a thread that has made no previous commitment to receive any
@@ -355,6 +905,36 @@ impl<TAIL: MessageSemantics, TYPE, const TYPENO: u16> MessageSemantics
     }
 }
 
+/// Like [Processing], but for a type number on which RPC requests are received
+pub struct ProcessingRpc<TAIL: MessageSemantics, Req, const TYPENO: u16> {
+    tail: TAIL,
+    _type: PhantomData<Req>,
+}
+
+impl<TAIL: MessageSemantics, Req, const TYPENO: u16> MessageSemantics
+    for ProcessingRpc<TAIL, Req, TYPENO>
+{
+    fn typeno_is_known(&self, typeno: u16) -> bool {
+        if typeno == TYPENO {
+            true
+        } else {
+            self.tail.typeno_is_known(typeno)
+        }
+    }
+
+    /// Requests that were not decoded are dropped, and their callers are unblocked with a reply
+    /// indicating that they were not answered.
+    unsafe fn drop(message: &mut ReceivedMessage<'_, Self>) {
+        if message.msg.type_ == TYPENO {
+            let req: Req = message.extract();
+            drop(req);
+            send_unanswered(message.msg.sender_pid);
+        } else {
+            TAIL::drop(core::mem::transmute(message))
+        }
+    }
+}
+
 pub use crate::msg::MsgSender as Sender;
 
 /// A message that was received while S was the current thread's semantics.
@@ -426,4 +1006,32 @@ impl<'a, S: MessageSemantics> ReceivedMessage<'a, S> {
             Err(self)
         }
     }
+
+    /// Decode a request that came in on an [RpcPort], and pass it to the closure along with a
+    /// handle through which it needs to be answered.
+    pub fn decode_rpc<
+        R,
+        F: FnOnce(Sender, Req, ReplyHandle<Resp, TYPENO>) -> R,
+        Req: Send,
+        Resp: Send,
+        const TYPENO: u16,
+    >(
+        mut self,
+        _port: &'a RpcPort<Req, Resp, TYPENO>,
+        f: F,
+    ) -> Result<R, ReceivedMessage<'a, S>> {
+        // As in decode, the port is only there to carry the type information
+        if self.msg.type_ == TYPENO {
+            let transmuted = unsafe { self.extract() };
+            let sender = self.sender();
+            let handle = ReplyHandle {
+                caller: self.msg.sender_pid,
+                _phantom: PhantomData,
+            };
+            core::mem::forget(self); // Or else the value would be double-dropped
+            Ok(f(sender, transmuted, handle))
+        } else {
+            Err(self)
+        }
+    }
 }
@@ -1,6 +1,7 @@
 //! Data-carrying mutex built using RIOT's [mutex] module
 //!
-//! This roughly mimics [std::sync::Mutex].
+//! This roughly mimics [std::sync::Mutex]. For data that is shared between async tasks, there is
+//! the [AsyncMutex], which can also be locked from regular threads.
 //!
 //! [mutex]: https://doc.riot-os.org/group__core__sync__mutex.html
 //! [std::sync::mutex]: https://doc.rust-lang.org/std/sync/struct.Mutex.html
@@ -11,6 +12,10 @@ use core::ops::{Deref, DerefMut};
 // allocated in .data and not in .text. (In other words: This is what allows transmuting the & to
 // the inner data into a &mut).
 use core::cell::UnsafeCell;
+use core::mem::ManuallyDrop;
+use core::task::Poll;
+
+use crate::helpers::WakerSlot;
 
 /// A mutual exclusion primitive useful for protecting shared data
 ///
@@ -56,6 +61,43 @@ impl<T> Mutex<T> {
             .lock()
     }
 
+    /// Get an accessor to the mutex when the mutex becomes available within the timeout
+    ///
+    /// ## Panics
+    ///
+    /// This function checks at runtime whether it is called in a thread context, and panics
+    /// otherwise. As with [`.lock()`](Self::lock), this can be avoided by calling
+    /// [`.lock_timeout()`](crate::thread::ValueInThread<&Mutex<T>>::lock_timeout) on a promoted
+    /// reference.
+    #[cfg(riot_module_ztimer)]
+    #[doc(alias = "ztimer_mutex_lock_timeout")]
+    pub fn lock_timeout<const HZ: u32>(
+        &self,
+        clock: &crate::ztimer::Clock<HZ>,
+        timeout: crate::ztimer::Ticks<HZ>,
+    ) -> Option<MutexGuard<'_, T>> {
+        crate::thread::InThread::new()
+            .expect("Mutex::lock_timeout may only be called outside of interrupt contexts")
+            .promote(self)
+            .lock_timeout(clock, timeout)
+    }
+
+    /// Prepare to lock the mutex in the current thread in a way that can be canceled by other
+    /// threads (or interrupts)
+    ///
+    /// See [CancelableLock] for details.
+    #[doc(alias = "mutex_cancel_init")]
+    pub fn cancelable_lock(&self) -> CancelableLock<'_, T> {
+        CancelableLock {
+            mutex: self,
+            // unsafe: C function only initializes the struct with the mutex and the current thread
+            cancel: UnsafeCell::new(unsafe {
+                riot_sys::mutex_cancel_init(crate::inline_cast_mut(self.mutex.get()))
+            }),
+            pid: crate::thread::get_pid(),
+        }
+    }
+
     /// Get an accessor to the mutex if the mutex is available
     #[doc(alias = "mutex_trylock")]
     pub fn try_lock(&self) -> Option<MutexGuard<'_, T>> {
@@ -109,6 +151,31 @@ impl<'a, T> crate::thread::ValueInThread<&'a Mutex<T>> {
         unsafe { riot_sys::mutex_lock(crate::inline_cast_mut(self.mutex.get())) };
         MutexGuard { mutex: &self }
     }
+
+    /// Get an accessor to the mutex when the mutex becomes available within the timeout
+    ///
+    /// Through the [crate::thread::ValueInThread], this is already guaranteed to run in a thread
+    /// context, so no additional check is performed.
+    #[cfg(riot_module_ztimer)]
+    #[doc(alias = "ztimer_mutex_lock_timeout")]
+    pub fn lock_timeout<const HZ: u32>(
+        self,
+        clock: &crate::ztimer::Clock<HZ>,
+        timeout: crate::ztimer::Ticks<HZ>,
+    ) -> Option<MutexGuard<'a, T>> {
+        // unsafe: As in lock; the clock is valid by construction.
+        let result = unsafe {
+            riot_sys::ztimer_mutex_lock_timeout(
+                crate::inline_cast_mut(clock.0),
+                crate::inline_cast_mut(self.mutex.get()),
+                timeout.0,
+            )
+        };
+        match result {
+            0 => Some(MutexGuard { mutex: &self }),
+            _ => None,
+        }
+    }
 }
 
 unsafe impl<T: Send> Send for Mutex<T> {}
@@ -120,6 +187,61 @@ impl<T: core::default::Default> core::default::Default for Mutex<T> {
     }
 }
 
+/// A prepared attempt to lock a [Mutex] from a particular thread, which can be canceled by any
+/// other thread or interrupt
+///
+/// This is created through [`Mutex::cancelable_lock()`] in the thread that is going to lock the
+/// mutex, and can then be shared (eg. with a supervising thread, which may call
+/// [`.cancel()`](Self::cancel) if the locking thread is stuck waiting for too long).
+///
+/// Once canceled, any pending and future [`.lock()`](Self::lock) calls fail; to lock the mutex
+/// again, a new cancelable lock needs to be created.
+pub struct CancelableLock<'a, T> {
+    mutex: &'a Mutex<T>,
+    cancel: UnsafeCell<riot_sys::inline::mutex_cancel_t>,
+    /// Thread for which the cancel structure was initialized
+    pid: crate::thread::KernelPID,
+}
+
+// unsafe: The cancel structure is only accessed through C functions that are safe to call
+// concurrently with the mutex_lock_cancelable call on it in the thread it was created for.
+unsafe impl<'a, T: Send> Sync for CancelableLock<'a, T> {}
+
+impl<'a, T> CancelableLock<'a, T> {
+    /// Get an accessor to the mutex when the mutex is available, unless the lock has been
+    /// canceled before or while waiting.
+    ///
+    /// ## Panics
+    ///
+    /// ... if called from any other context than the thread in which the cancelable lock was
+    /// created.
+    #[doc(alias = "mutex_lock_cancelable")]
+    pub fn lock(&self) -> Option<MutexGuard<'a, T>> {
+        assert!(
+            crate::thread::InThread::new().is_ok() && crate::thread::get_pid() == self.pid,
+            "Cancelable lock may only be used in the thread it was created in"
+        );
+        // unsafe: Preconditions of the C function are met, as the struct was initialized for the
+        // current thread.
+        let result =
+            unsafe { riot_sys::mutex_lock_cancelable(crate::inline_cast_mut(self.cancel.get())) };
+        match result {
+            0 => Some(MutexGuard { mutex: self.mutex }),
+            _ => None,
+        }
+    }
+
+    /// Make any pending or future [`.lock()`](Self::lock) attempt fail.
+    ///
+    /// This can be called from any thread, and from interrupts. If the mutex has already been
+    /// locked through this, the guard stays valid.
+    #[doc(alias = "mutex_cancel")]
+    pub fn cancel(&self) {
+        // unsafe: C function can be called at any time
+        unsafe { riot_sys::mutex_cancel(crate::inline_cast_mut(self.cancel.get())) };
+    }
+}
+
 /// A lock on a mutex
 ///
 /// Though a MutexGuard, a mutex's inner value can be mutably accessed; the creation mechanism of
@@ -137,6 +259,12 @@ impl<'a, T> Drop for MutexGuard<'a, T> {
 }
 
 impl<'a, T> MutexGuard<'a, T> {
+    /// Pointer to the locked RIOT mutex, for use by C functions that unlock and re-lock it
+    /// (while this guard is logically held by the caller)
+    pub(crate) fn raw_mutex(&self) -> *mut riot_sys::inline::mutex_t {
+        self.mutex.mutex.get()
+    }
+
     /// Put the current thread to sleep right after unlocking the mutex. This is equivalent to
     /// calling mutex_unlock_and_sleep in RIOT.
     #[doc(alias = "mutex_unlock_and_sleep")]
@@ -168,3 +296,127 @@ impl<T> mutex_trait::Mutex for &Mutex<T> {
         f(&mut Mutex::lock(self))
     }
 }
+
+/// A mutex that can be locked asynchronously, without blocking the thread
+///
+/// Waiting for a [Mutex] blocks the whole thread, which deadlocks when the mutex is held by another
+/// task running in the same executor. An [`.lock()`](Self::lock) on this mutex instead yields to
+/// other tasks until the mutex becomes available, at which point the task is woken through its
+/// waker (which, with the [executor](crate::executor), sets a thread flag).
+///
+/// Regular threads can use the same mutex through [`.blocking_lock()`](Self::blocking_lock).
+/// Either way, the lock is an [AsyncMutexGuard], which wakes any waiting task when dropped.
+///
+/// Waking works best when only a single task waits for the mutex at any time; with more tasks,
+/// they take turns in being polled until one succeeds.
+///
+/// ```
+/// # #![no_std]
+/// # #![feature(start)]
+/// # #[start]
+/// # fn main(_argc: isize, _argv: *const *const u8) -> isize { panic!("Doc tests are not supposed to be run") }
+/// # use riot_wrappers::mutex::AsyncMutex;
+/// # use riot_wrappers::ztimer::{Clock, Ticks};
+/// static COUNTER: AsyncMutex<u32> = AsyncMutex::new(0);
+///
+/// async fn count_slowly() {
+///     let mut counter = COUNTER.lock().await;
+///     // Other tasks keep running while this one holds the lock
+///     Clock::msec().sleep_async(Ticks(10)).await;
+///     *counter += 1;
+/// }
+/// ```
+pub struct AsyncMutex<T> {
+    mutex: Mutex<T>,
+    /// Task of a [`.lock()`](Self::lock) future waiting for the mutex to be released
+    waker: WakerSlot,
+}
+
+impl<T> AsyncMutex<T> {
+    /// Create a new mutex in an unlocked state
+    pub const fn new(t: T) -> Self {
+        AsyncMutex {
+            mutex: Mutex::new(t),
+            waker: WakerSlot::new(),
+        }
+    }
+
+    /// Get an accessor to the mutex once it is available, letting other tasks run in the meantime
+    pub async fn lock(&self) -> AsyncMutexGuard<'_, T> {
+        core::future::poll_fn(|context| {
+            if let Some(guard) = self.try_lock() {
+                return Poll::Ready(guard);
+            }
+            self.waker.register(context.waker());
+            // The mutex may have been released between the attempt and the registration
+            match self.try_lock() {
+                Some(guard) => Poll::Ready(guard),
+                None => Poll::Pending,
+            }
+        })
+        .await
+    }
+
+    /// Get an accessor to the mutex when the mutex is available, blocking the thread until then
+    ///
+    /// ## Panics
+    ///
+    /// ... when called from an interrupt.
+    #[doc(alias = "mutex_lock")]
+    pub fn blocking_lock(&self) -> AsyncMutexGuard<'_, T> {
+        self.wrap(self.mutex.lock())
+    }
+
+    /// Get an accessor to the mutex if the mutex is available
+    #[doc(alias = "mutex_trylock")]
+    pub fn try_lock(&self) -> Option<AsyncMutexGuard<'_, T>> {
+        self.mutex.try_lock().map(|guard| self.wrap(guard))
+    }
+
+    fn wrap<'a>(&'a self, guard: MutexGuard<'a, T>) -> AsyncMutexGuard<'a, T> {
+        AsyncMutexGuard {
+            guard: ManuallyDrop::new(guard),
+            waker: &self.waker,
+        }
+    }
+}
+
+impl<T: core::default::Default> core::default::Default for AsyncMutex<T> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+/// A lock on an [AsyncMutex]
+///
+/// This behaves like a [MutexGuard]: The mutex's inner value can be mutably accessed through it,
+/// and when it is dropped, the mutex becomes available again (and a task waiting for it is woken).
+pub struct AsyncMutexGuard<'a, T> {
+    guard: ManuallyDrop<MutexGuard<'a, T>>,
+    waker: &'a WakerSlot,
+}
+
+impl<'a, T> Drop for AsyncMutexGuard<'a, T> {
+    fn drop(&mut self) {
+        // The mutex needs to be unlocked before waking, lest the woken task (which may run right
+        // away if it is in a more urgent thread) find it still locked and wait forever.
+        //
+        // unsafe: Dropped only here, and not used afterwards
+        unsafe { ManuallyDrop::drop(&mut self.guard) };
+        self.waker.wake();
+    }
+}
+
+impl<'a, T> Deref for AsyncMutexGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.guard
+    }
+}
+
+impl<'a, T> DerefMut for AsyncMutexGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
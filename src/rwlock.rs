@@ -1,80 +1,149 @@
+//! Data-carrying reader-writer lock built using RIOT's [mutex] module
+//!
+//! This roughly mimics [std::sync::RwLock].
+//!
+//! [mutex]: https://doc.riot-os.org/group__core__sync__mutex.html
+//! [std::sync::RwLock]: https://doc.rust-lang.org/std/sync/struct.RwLock.html
+
+use core::cell::UnsafeCell;
 use core::ops::{Deref, DerefMut};
 
-/// A mutual exclusion primitive similar to std::sync::RwLock, implemented using RIOT's rwlock.
+/// A reader-writer lock similar to [std::sync::RwLock]
 ///
-/// Like crate::mutex::Mutex, this knows no poisoning.
+/// Like [crate::mutex::Mutex], this knows no poisoning.
 ///
-/// It's unknown whether it's actually a good idea to go this way (with all the
+/// The lock is implemented using a single RIOT mutex, which is held either by the writer or on
+/// behalf of all readers while there are any, and a reader count that is managed with interrupts
+/// disabled.
+///
+/// ## Writer starvation
+///
+/// The lock is reader-preferring: As long as new readers keep arriving before the last one
+/// leaves, a thread blocked in [`.write()`](Self::write) does not get the lock, no matter its
+/// priority. Where readers hold the lock most of the time, writers should use
+/// [`.try_write()`](Self::try_write) in a loop that leaves gaps, or a [crate::mutex::Mutex] should
+/// be used instead.
+///
+/// Non-blocking operations (`try_read`, `try_write` and dropping the guards) can be used in
+/// interrupts.
+///
+/// [std::sync::RwLock]: https://doc.rust-lang.org/std/sync/struct.RwLock.html
 pub struct RwLock<T> {
-    rwlock: riot_sys::pthread_rwlock_t,
-    data: T,
+    /// Held by the writer, or on behalf of all readers while `readers` is nonzero
+    lock: UnsafeCell<riot_sys::inline::mutex_t>,
+    /// Number of read guards that are out; only accessed with interrupts disabled
+    readers: UnsafeCell<usize>,
+    data: UnsafeCell<T>,
 }
 
 unsafe impl<T: Send> Send for RwLock<T> {}
 unsafe impl<T: Send + Sync> Sync for RwLock<T> {}
 
 impl<T> RwLock<T> {
-    pub fn new(t: T) -> RwLock<T> {
-        // FIXME consider deriving default
-        let mut rwlock = riot_sys::pthread_rwlock_t {
-            mutex: riot_sys::mutex_t {
-                queue: riot_sys::list_node { next: 0 as *mut _ },
-            },
-            queue: riot_sys::priority_queue_t { first: 0 as *mut _ },
-            readers: 0,
-        };
-        // FIXME actually needless given it only memsets
-        unsafe { riot_sys::pthread_rwlock_init(&mut rwlock, 0 as *mut _) };
+    /// Create a new lock in an unlocked state
+    pub const fn new(t: T) -> RwLock<T> {
+        // unsafe: Side effect free C macro
+        let lock = unsafe { riot_sys::macro_MUTEX_INIT() };
         RwLock {
-            rwlock: rwlock,
-            data: t,
+            lock: UnsafeCell::new(lock),
+            readers: UnsafeCell::new(0),
+            data: UnsafeCell::new(t),
         }
     }
 
+    /// Count in another reader if there are readers already; returns false if the caller needs to
+    /// obtain the lock first.
+    fn join_readers(&self) -> bool {
+        crate::interrupt::free(|_| {
+            // unsafe: Only accessed with interrupts disabled
+            let readers = unsafe { &mut *self.readers.get() };
+            if *readers > 0 {
+                *readers += 1;
+                true
+            } else {
+                false
+            }
+        })
+    }
+
+    /// Count in the first reader after having obtained the lock
+    fn start_readers(&self) {
+        crate::interrupt::free(|_| {
+            // unsafe: Only accessed with interrupts disabled
+            unsafe { *self.readers.get() += 1 };
+        })
+    }
+
+    /// Get shared access to the data, blocking while there is a writer
+    ///
+    /// ## Panics
+    ///
+    /// This function checks at runtime whether it is called in a thread context, and panics
+    /// otherwise.
     pub fn read(&self) -> RwLockReadGuard<T> {
-        match unsafe { riot_sys::pthread_rwlock_rdlock(&self.rwlock as *const _ as *mut _) } {
-            0 => RwLockReadGuard { rwlock: self },
-            _ => panic!("Unexpected error from pthread_rwlock_rdlock"),
+        crate::thread::InThread::new()
+            .expect("RwLock::read may only be called outside of interrupt contexts");
+        if !self.join_readers() {
+            // unsafe: All preconditions of the C function are met (initialized by construction,
+            // running in a thread as checked)
+            unsafe { riot_sys::mutex_lock(crate::inline_cast_mut(self.lock.get())) };
+            // If some other reader came along and took the lock in the meantime, we're waiting
+            // for that group to end -- but that's correct, if not ideal.
+            self.start_readers();
         }
+        RwLockReadGuard { rwlock: self }
     }
 
+    /// Get shared access to the data if there is no writer
     pub fn try_read(&self) -> Option<RwLockReadGuard<T>> {
-        match unsafe { riot_sys::pthread_rwlock_tryrdlock(&self.rwlock as *const _ as *mut _) }
-            as u32
-        {
-            0 => Some(RwLockReadGuard { rwlock: self }),
-            riot_sys::EBUSY => None,
-            _ => panic!("Unexpected error from pthread_rwlock_tryrdlock"),
+        if !self.join_readers() {
+            // unsafe: Initialized by construction; the non-blocking C function can be called in
+            // any context
+            match unsafe { riot_sys::mutex_trylock(crate::inline_cast_mut(self.lock.get())) } {
+                1 => self.start_readers(),
+                _ => return None,
+            }
         }
+        Some(RwLockReadGuard { rwlock: self })
     }
 
+    /// Get exclusive access to the data, blocking while there are readers or a writer
+    ///
+    /// This can wait indefinitely while readers keep overlapping; see [writer
+    /// starvation](Self#writer-starvation).
+    ///
+    /// ## Panics
+    ///
+    /// This function checks at runtime whether it is called in a thread context, and panics
+    /// otherwise.
     pub fn write(&self) -> RwLockWriteGuard<T> {
-        match unsafe { riot_sys::pthread_rwlock_wrlock(&self.rwlock as *const _ as *mut _) } {
-            0 => RwLockWriteGuard { rwlock: self },
-            _ => panic!("Unexpected error from pthread_rwlock_wrlock"),
-        }
+        crate::thread::InThread::new()
+            .expect("RwLock::write may only be called outside of interrupt contexts");
+        // unsafe: All preconditions of the C function are met (initialized by construction,
+        // running in a thread as checked)
+        unsafe { riot_sys::mutex_lock(crate::inline_cast_mut(self.lock.get())) };
+        RwLockWriteGuard { rwlock: self }
     }
 
+    /// Get exclusive access to the data if there are neither readers nor a writer
     pub fn try_write(&self) -> Option<RwLockWriteGuard<T>> {
-        match unsafe { riot_sys::pthread_rwlock_trywrlock(&self.rwlock as *const _ as *mut _) }
-            as u32
-        {
-            0 => Some(RwLockWriteGuard { rwlock: self }),
-            riot_sys::EBUSY => None,
-            _ => panic!("Unexpected error from pthread_rwlock_trywrlock"),
+        // unsafe: Initialized by construction; the non-blocking C function can be called in any
+        // context
+        match unsafe { riot_sys::mutex_trylock(crate::inline_cast_mut(self.lock.get())) } {
+            1 => Some(RwLockWriteGuard { rwlock: self }),
+            _ => None,
         }
     }
 }
 
-impl<T> Drop for RwLock<T> {
-    fn drop(&mut self) {
-        match unsafe { riot_sys::pthread_rwlock_destroy(&mut self.rwlock) } {
-            0 => (),
-            _ => panic!("pthread_rwlock_destroy was unsuccessful, even though RwLock has no other references to it.")
-        }
+impl<T: core::default::Default> core::default::Default for RwLock<T> {
+    fn default() -> Self {
+        Self::new(Default::default())
     }
 }
 
+/// Shared access to the data of an [RwLock], obtained through [`RwLock::read()`] or
+/// [`RwLock::try_read()`]
 pub struct RwLockReadGuard<'a, T> {
     rwlock: &'a RwLock<T>,
 }
@@ -83,20 +152,31 @@ impl<'a, T> Deref for RwLockReadGuard<'a, T> {
     type Target = T;
 
     fn deref(&self) -> &T {
-        &self.rwlock.data
+        // unsafe: The lock is held on behalf of the readers, so there is no writer, and other
+        // readers only create shared references
+        unsafe { &*self.rwlock.data.get() }
     }
 }
 
 impl<'a, T> Drop for RwLockReadGuard<'a, T> {
     fn drop(&mut self) {
-        match unsafe { riot_sys::pthread_rwlock_unlock(&self.rwlock.rwlock as *const _ as *mut _) }
-        {
-            0 => (),
-            _ => panic!("pthread_rwlock_unlock was unsuccessful."),
+        let last = crate::interrupt::free(|_| {
+            // unsafe: Only accessed with interrupts disabled
+            let readers = unsafe { &mut *self.rwlock.readers.get() };
+            *readers -= 1;
+            *readers == 0
+        });
+        if last {
+            // Unlocking on behalf of the reader that locked it (which RIOT mutexes allow).
+            // unsafe: The lock is held by the readers, of which this was the last; unlocking can
+            // be done in any context
+            unsafe { riot_sys::mutex_unlock(crate::inline_cast_mut(self.rwlock.lock.get())) };
         }
     }
 }
 
+/// Exclusive access to the data of an [RwLock], obtained through [`RwLock::write()`] or
+/// [`RwLock::try_write()`]
 pub struct RwLockWriteGuard<'a, T> {
     rwlock: &'a RwLock<T>,
 }
@@ -105,22 +185,21 @@ impl<'a, T> Deref for RwLockWriteGuard<'a, T> {
     type Target = T;
 
     fn deref(&self) -> &T {
-        &self.rwlock.data
+        // unsafe: The lock is held by this guard, and any mutable reference is borrowed from it
+        unsafe { &*self.rwlock.data.get() }
     }
 }
 
 impl<'a, T> DerefMut for RwLockWriteGuard<'a, T> {
     fn deref_mut(&mut self) -> &mut T {
-        unsafe { &mut *(&self.rwlock.data as *const _ as *mut _) }
+        // unsafe: The lock is held by this guard, which is borrowed exclusively
+        unsafe { &mut *self.rwlock.data.get() }
     }
 }
 
 impl<'a, T> Drop for RwLockWriteGuard<'a, T> {
     fn drop(&mut self) {
-        match unsafe { riot_sys::pthread_rwlock_unlock(&self.rwlock.rwlock as *const _ as *mut _) }
-        {
-            0 => (),
-            _ => panic!("pthread_rwlock_unlock was unsuccessful."),
-        }
+        // unsafe: The lock is held by this guard; unlocking can be done in any context
+        unsafe { riot_sys::mutex_unlock(crate::inline_cast_mut(self.rwlock.lock.get())) }
     }
 }
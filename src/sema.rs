@@ -0,0 +1,134 @@
+//! Counting semaphores built using RIOT's [sema](https://doc.riot-os.org/group__sys__sema.html)
+//! module
+//!
+//! Unlike a [Mutex](crate::mutex::Mutex), a [Semaphore] does not protect any data and has no
+//! owner: Any thread or interrupt can [post](Semaphore::post) to it, and any thread can take
+//! (wait for) the posted units. This makes it suitable for producer/consumer patterns.
+
+use core::cell::UnsafeCell;
+use core::task::Poll;
+
+use crate::error::{NegativeErrorExt, NumericError};
+use crate::helpers::WakerSlot;
+
+/// A counting semaphore
+pub struct Semaphore {
+    sema: UnsafeCell<riot_sys::sema_t>,
+    /// Task of an [`.acquire()`](Self::acquire) future waiting for a post
+    waker: WakerSlot,
+}
+
+// unsafe: All access to the sema_t goes through the C functions, which are thread safe (and, for
+// posting, interrupt safe)
+unsafe impl Send for Semaphore {}
+unsafe impl Sync for Semaphore {}
+
+impl Semaphore {
+    /// Create a new semaphore with an initial value
+    #[doc(alias = "sema_create")]
+    pub const fn new(value: u32) -> Self {
+        // unsafe: An all-zero sema_t is one with value 0, state SEMA_OK and an unlocked mutex (as
+        // in the SEMA_CREATE macro)
+        let mut sema: riot_sys::sema_t = unsafe { core::mem::zeroed() };
+        sema.value = value as _;
+        Semaphore {
+            sema: UnsafeCell::new(sema),
+            waker: WakerSlot::new(),
+        }
+    }
+
+    /// Current value of the semaphore
+    #[doc(alias = "sema_get_value")]
+    pub fn value(&self) -> u32 {
+        // unsafe: C function only reads
+        unsafe { riot_sys::sema_get_value(crate::inline_cast_mut(self.sema.get())) as _ }
+    }
+
+    /// Increment the semaphore's value, waking up a waiting thread or task.
+    ///
+    /// This can be called from any thread, and from interrupts. It fails with
+    /// [EOVERFLOW](crate::error::EOVERFLOW) if the value can not be incremented any further.
+    #[doc(alias = "sema_post")]
+    pub fn post(&self) -> Result<(), NumericError> {
+        // unsafe: C function can be called from any context on an initialized semaphore
+        unsafe { riot_sys::sema_post(self.sema.get()) }.negative_to_error()?;
+        self.waker.wake();
+        Ok(())
+    }
+
+    /// Decrement the semaphore's value, blocking while it is 0.
+    ///
+    /// ## Panics
+    ///
+    /// ... when called from an interrupt.
+    #[doc(alias = "sema_wait")]
+    pub fn wait(&self) {
+        crate::thread::InThread::new()
+            .expect("Semaphore::wait may only be called outside of interrupt contexts");
+        // unsafe: Running in a thread as checked
+        let result = unsafe { riot_sys::sema_wait(crate::inline_cast_mut(self.sema.get())) };
+        // Only fails if the semaphore was destroyed, which this wrapper never does
+        debug_assert!(result == 0, "Semaphore wait failed");
+    }
+
+    /// Decrement the semaphore's value if it is not 0, or fail with
+    /// [EAGAIN](crate::error::EAGAIN).
+    ///
+    /// This can be called from any context.
+    #[doc(alias = "sema_try_wait")]
+    pub fn try_wait(&self) -> Result<(), NumericError> {
+        // unsafe: C function does not block, and can thus be called from any context
+        unsafe { riot_sys::sema_try_wait(crate::inline_cast_mut(self.sema.get())) }
+            .negative_to_error()
+            .map(|_| ())
+    }
+
+    /// Decrement the semaphore's value, blocking while it is 0 for at most `timeout` on the given
+    /// clock.
+    ///
+    /// If the timeout expires, this fails with [ETIMEDOUT](crate::error::ETIMEDOUT).
+    ///
+    /// ## Panics
+    ///
+    /// ... when called from an interrupt.
+    #[cfg(riot_module_ztimer)]
+    #[doc(alias = "sema_wait_timed_ztimer")]
+    pub fn wait_timeout<const HZ: u32>(
+        &self,
+        clock: &crate::ztimer::Clock<HZ>,
+        timeout: crate::ztimer::Ticks<HZ>,
+    ) -> Result<(), NumericError> {
+        crate::thread::InThread::new()
+            .expect("Semaphore::wait_timeout may only be called outside of interrupt contexts");
+        // unsafe: Running in a thread as checked; the clock is valid by construction
+        unsafe {
+            riot_sys::sema_wait_timed_ztimer(
+                crate::inline_cast_mut(self.sema.get()),
+                crate::inline_cast_mut(clock.0),
+                timeout.0,
+            )
+        }
+        .negative_to_error()
+        .map(|_| ())
+    }
+
+    /// Decrement the semaphore's value, asynchronously waiting while it is 0.
+    ///
+    /// The returned future is woken when the semaphore is [posted](Self::post) to. This works best
+    /// when only a single task waits for the semaphore at any time; with more tasks, they take
+    /// turns in being polled until one succeeds.
+    pub async fn acquire(&self) {
+        core::future::poll_fn(|context| {
+            if self.try_wait().is_ok() {
+                return Poll::Ready(());
+            }
+            self.waker.register(context.waker());
+            // A post may have happened between the attempt and the registration
+            match self.try_wait() {
+                Ok(()) => Poll::Ready(()),
+                Err(_) => Poll::Pending,
+            }
+        })
+        .await
+    }
+}
@@ -0,0 +1,259 @@
+//! A safe-to-use interface for [thread flags](https://doc.riot-os.org/group__core__thread__flags.html)
+//!
+//! Thread flags are a 16 bit field in each thread that other threads and interrupts can set, and
+//! on which the thread can block until one or all of a set of flags are set.
+//!
+//! Similar to how [`msg::v2`](crate::msg::v2) handles message type numbers, the flags of a thread
+//! are managed through a [FlagSemantics] object that is obtained from the thread's [StartToken]
+//! using [`.take_flag_semantics()`](super::TokenParts::take_flag_semantics). From that, pairs of
+//! [FlagReceiver]s (which stay in the thread) and [FlagSender]s (which can be passed to other
+//! threads or to interrupts) are split off. Flags are allocated by the [FlagSemantics], so two
+//! components can not accidentally use the same flag.
+//!
+//! Flags that are predefined by RIOT (eg. `THREAD_FLAG_MSG_WAITING`), as well as the flag used by
+//! the [executor](crate::executor), are never handed out. Flags that RIOT modules use with a fixed
+//! value (eg. `THREAD_FLAG_EVENT`) are not handed out by
+//! [`.split_off()`](FlagSemantics::split_off) either, but remain available to the wrappers of
+//! those modules.
+//!
+//! ## Example
+//!
+//! ```
+//! # #![no_std]
+//! # #![feature(start)]
+//! # #[start]
+//! # fn main(_argc: isize, _argv: *const *const u8) -> isize { panic!("Doc tests are not supposed to be run") }
+//! # use riot_wrappers::thread::*;
+//! fn thread(tok: StartToken) -> EndToken {
+//!     let (tok, mut semantics) = tok.take_flag_semantics();
+//!     let (receiver, sender) = semantics.split_off();
+//!     // Pass the sender on to an interrupt handler that calls `sender.set()` ...
+//!     receiver.wait_any();
+//!     // ... and recover it when it is not used any more
+//!     semantics.return_sender(sender);
+//!     semantics.return_receiver(receiver);
+//!     let tok = tok.return_flag_semantics(semantics);
+//!     tok.can_end()
+//! }
+//! ```
+//!
+//! [StartToken]: super::StartToken
+
+use core::marker::PhantomData;
+
+use super::KernelPID;
+
+/// Flags with a predefined meaning in RIOT (or in this crate, as for the [executor's wake-up
+/// flag](crate::executor)), which are never handed out by a [FlagSemantics]
+const PREDEFINED: u16 = (riot_sys::THREAD_FLAG_MSG_WAITING | riot_sys::THREAD_FLAG_TIMEOUT) as u16
+    | crate::executor::WAKE_FLAG;
+
+/// Flags that RIOT modules use with a fixed value, which are only ever assigned through
+/// [`.split_off_specific()`](FlagSemantics::split_off_specific) by the components wrapping those
+/// modules
+#[cfg(riot_module_event)]
+const FIXED: u16 = riot_sys::THREAD_FLAG_EVENT as u16;
+#[cfg(not(riot_module_event))]
+const FIXED: u16 = 0;
+
+/// Thread-bound record of which of the current thread's flags have been assigned a meaning.
+///
+/// This is obtained through
+/// [`TokenParts::take_flag_semantics()`](super::TokenParts::take_flag_semantics), and needs to be
+/// returned (with all flags returned to it) before the thread can end.
+#[derive(Debug)]
+pub struct FlagSemantics {
+    /// Flags for which a [FlagReceiver] is currently out
+    receivers_out: u16,
+    /// Flags for which a [FlagSender] is currently out
+    senders_out: u16,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl FlagSemantics {
+    /// Create a new FlagSemantics object to split flags off of.
+    ///
+    /// **Conditions**, violating which is a safety violation:
+    ///
+    /// * No other FlagSemantics may exist for the current thread.
+    ///
+    /// * The thread must not terminate while flags are out (which is ensured when this is obtained
+    ///   through the [super::StartToken]).
+    pub(crate) unsafe fn new() -> Self {
+        FlagSemantics {
+            receivers_out: 0,
+            senders_out: 0,
+            _not_send: PhantomData,
+        }
+    }
+
+    fn claimed(&self) -> u16 {
+        PREDEFINED | self.receivers_out | self.senders_out
+    }
+
+    /// Assign a meaning to a single, previously unused flag.
+    ///
+    /// The returned [FlagReceiver] stays in the current thread, whereas the [FlagSender] can be
+    /// passed on to other threads or interrupts.
+    ///
+    /// # Panics
+    ///
+    /// ... if all flags of the thread have already been assigned.
+    pub fn split_off(&mut self) -> (FlagReceiver, FlagSender) {
+        let free = !(self.claimed() | FIXED);
+        assert!(free != 0, "All thread flags are already in use");
+        // Picking the lowest-valued free flag
+        self.split_off_specific(free & free.wrapping_neg())
+    }
+
+    /// Assign a meaning to a particular flag (or set of flags).
+    ///
+    /// This is used by components that need to work with the flags with which RIOT components
+    /// were built (eg. `THREAD_FLAG_EVENT`).
+    ///
+    /// # Panics
+    ///
+    /// ... if any of the flags has already been assigned.
+    pub(crate) fn split_off_specific(&mut self, mask: u16) -> (FlagReceiver, FlagSender) {
+        assert!(
+            self.claimed() & mask == 0,
+            "Thread flag is already in use for this thread."
+        );
+        self.receivers_out |= mask;
+        self.senders_out |= mask;
+        (
+            FlagReceiver {
+                mask,
+                _not_send: PhantomData,
+            },
+            FlagSender {
+                destination: super::get_pid(),
+                mask,
+            },
+        )
+    }
+
+    /// Return a receiver previously obtained from [`.split_off()`](Self::split_off) (possibly after
+    /// having merged it with others).
+    ///
+    /// Any of its flags that are still set are cleared.
+    ///
+    /// A flag becomes available for reassignment once both its receiver and its sender have been
+    /// returned.
+    pub fn return_receiver(&mut self, receiver: FlagReceiver) {
+        // Receivers are not Send, and thus necessarily come from this thread's semantics.
+        debug_assert!(self.receivers_out & receiver.mask == receiver.mask);
+        receiver.try_take();
+        self.receivers_out &= !receiver.mask;
+    }
+
+    /// Return a sender previously obtained from [`.split_off()`](Self::split_off).
+    ///
+    /// Once this is done, nothing can set the sender's flag on this thread any more.
+    ///
+    /// # Panics
+    ///
+    /// ... if the sender was created for a different thread.
+    pub fn return_sender(&mut self, sender: FlagSender) {
+        assert!(
+            sender.destination == super::get_pid(),
+            "Sender was not split off this thread's flag semantics"
+        );
+        debug_assert!(self.senders_out & sender.mask == sender.mask);
+        self.senders_out &= !sender.mask;
+    }
+
+    /// Check whether all senders and receivers have been returned.
+    pub(crate) fn is_complete(&self) -> bool {
+        self.receivers_out == 0 && self.senders_out == 0
+    }
+}
+
+/// Thread-bound handle through which the thread waits for one or more flags to be set.
+#[derive(Debug)]
+pub struct FlagReceiver {
+    mask: u16,
+    // Flags are per thread, so this must stay in the thread it was created in.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl FlagReceiver {
+    /// The flags this receiver is waiting for
+    ///
+    /// This is useful to tell apart which flags were set after having [merged](Self::merge)
+    /// receivers.
+    pub fn mask(&self) -> u16 {
+        self.mask
+    }
+
+    /// Combine two receivers into one that can wait for the flags of either.
+    pub fn merge(self, other: FlagReceiver) -> FlagReceiver {
+        FlagReceiver {
+            mask: self.mask | other.mask,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Block until any of the receiver's flags is set.
+    ///
+    /// Returns the receiver's flags that were set, and clears them.
+    #[doc(alias = "thread_flags_wait_any")]
+    pub fn wait_any(&self) -> u16 {
+        // unsafe: C function can be called at any time in a thread, which the !Send ensures
+        unsafe { riot_sys::thread_flags_wait_any(self.mask) }
+    }
+
+    /// Block until all of the receiver's flags are set, and clear them.
+    #[doc(alias = "thread_flags_wait_all")]
+    pub fn wait_all(&self) {
+        // unsafe: C function can be called at any time in a thread, which the !Send ensures
+        unsafe { riot_sys::thread_flags_wait_all(self.mask) };
+    }
+
+    /// Clear the receiver's flags without blocking.
+    ///
+    /// Returns those flags that were set before.
+    #[doc(alias = "thread_flags_clear")]
+    pub fn try_take(&self) -> u16 {
+        // unsafe: C function can be called at any time
+        unsafe { riot_sys::thread_flags_clear(self.mask) }
+    }
+}
+
+/// Object through which a flag can be set on a precise thread.
+///
+/// Like a [`msg::v2::SendPort`](crate::msg::v2::SendPort), this can be sent to other threads, and
+/// it is guaranteed that the destination thread has not terminated.
+///
+/// It is deliberately not Clone: It needs to be returned to the thread's [FlagSemantics] before
+/// the thread can terminate. Multiple users can share it through shared references.
+#[derive(Debug)]
+pub struct FlagSender {
+    destination: KernelPID,
+    mask: u16,
+}
+
+impl FlagSender {
+    /// Set the flag on the destination thread.
+    ///
+    /// This can be called from any thread, and from interrupts.
+    #[doc(alias = "thread_flags_set")]
+    pub fn set(&self) {
+        let thread = self.destination.thread();
+        // Outside debug, the flag is silently not set, which is what would happen if the thread
+        // were just not waiting.
+        debug_assert!(
+            thread.is_ok(),
+            "Target PID vanished even though a FlagSender was still around"
+        );
+        if let Ok(thread) = thread {
+            // unsafe: C function can be called at any time with a valid thread
+            unsafe { riot_sys::thread_flags_set(thread as *mut _, self.mask) };
+        }
+    }
+
+    /// Access the thread on which the flag is set
+    pub fn destination(&self) -> KernelPID {
+        self.destination
+    }
+}
@@ -28,6 +28,17 @@ pub use tokenparts::{EndToken, InIsr, InThread, StartToken, ValueInThread};
 mod stack_stats;
 pub use stack_stats::{StackStats, StackStatsError};
 
+#[cfg(all(riot_module_schedstatistics, riot_module_ztimer_usec))]
+mod sched_stats;
+#[cfg(all(riot_module_schedstatistics, riot_module_ztimer_usec))]
+pub use sched_stats::{SchedSnapshot, SchedStats, ThreadUsage};
+
+#[cfg(riot_module_core_thread_flags)]
+pub mod flags;
+
+#[cfg(riot_module_sched_cb)]
+pub mod sched_cb;
+
 /// Error returned by PID methods when no thread with that PID exists
 #[derive(Debug)]
 pub struct NoSuchThread;
@@ -7,6 +7,9 @@ use crate::helpers::PointerToCStr;
 /// Offloaded tools for creation
 mod creation;
 pub use creation::{scope, spawn, CountedThread, CountingThreadScope};
+/// Builder style creation
+mod builder;
+pub use builder::{Builder, JoinHandle, ScopedJoinHandle};
 
 /// Wrapper around a valid (not necessarily running, but in-range) [riot_sys::kernel_pid_t] that
 /// provides access to thread details and signaling.
@@ -144,7 +147,7 @@ impl KernelPID {
 
     /// Pick the thread_t out of sched_threads for the PID
     #[doc(alias = "thread_get")]
-    fn thread(&self) -> Result<*const riot_sys::thread_t, NoSuchThread> {
+    pub(crate) fn thread(&self) -> Result<*const riot_sys::thread_t, NoSuchThread> {
         // unsafe: C function's "checked" precondition met by type constraint on PID validity
         let t = unsafe { riot_sys::thread_get_unchecked(self.0) };
         // .as_ref() would have the null check built in, but we can't build a shared reference out
@@ -161,6 +164,51 @@ impl KernelPID {
         Ok(unsafe { (*thread).priority })
     }
 
+    /// Change the thread's priority
+    ///
+    /// If this makes a different thread more urgent than the running one, that thread is switched
+    /// to right away.
+    ///
+    /// ## Panics
+    ///
+    /// ... if the priority is not below `SCHED_PRIO_LEVELS`.
+    #[doc(alias = "sched_change_priority")]
+    pub fn set_priority(&self, priority: u8) -> Result<(), NoSuchThread> {
+        assert!(
+            u32::from(priority) < riot_sys::SCHED_PRIO_LEVELS as u32,
+            "Priority out of range"
+        );
+        let thread = self.thread()?;
+        // unsafe: The thread exists and is not stopped (or it would have been removed from the
+        // thread list); as with the other accessors, it is up to the caller not to have it end
+        // concurrently. This is not done with interrupts disabled, as the function may need to
+        // switch threads.
+        unsafe { riot_sys::sched_change_priority(thread as *mut _, priority) };
+        Ok(())
+    }
+
+    /// Raise the thread's priority to at least `priority` until the returned guard is dropped.
+    ///
+    /// If the thread is already more urgent, its priority is left unchanged. When the guard is
+    /// dropped, the priority the thread had before is restored (if the thread still exists).
+    ///
+    /// Boosts of the same thread can be nested, but their guards need to be dropped in the reverse
+    /// order of their creation: Each guard restores the priority it found, so dropping an outer
+    /// guard before an inner one leaves the thread at the outer boost's priority.
+    ///
+    /// ## Panics
+    ///
+    /// ... under the same conditions as [`.set_priority()`](Self::set_priority).
+    pub fn boost_priority(&self, priority: u8) -> Result<PriorityBoost, NoSuchThread> {
+        let previous = self.priority()?;
+        // Lower numbers are more urgent
+        self.set_priority(priority.min(previous))?;
+        Ok(PriorityBoost {
+            pid: *self,
+            previous,
+        })
+    }
+
     /// Gather information about the stack's thread.
     ///
     /// A None being returned can have two reasons:
@@ -209,6 +257,24 @@ impl KernelPID {
     }
 }
 
+/// Guard returned by [`KernelPID::boost_priority()`] that restores the thread's previous
+/// priority when dropped.
+///
+/// See there for why nested guards need to be dropped in reverse order.
+#[derive(Debug)]
+#[must_use = "The priority is restored right away when the guard is dropped"]
+pub struct PriorityBoost {
+    pid: KernelPID,
+    previous: u8,
+}
+
+impl Drop for PriorityBoost {
+    fn drop(&mut self) {
+        // If the thread is gone, there is nothing to restore.
+        let _ = self.pid.set_priority(self.previous);
+    }
+}
+
 impl Into<raw::kernel_pid_t> for &KernelPID {
     fn into(self) -> raw::kernel_pid_t {
         self.0
@@ -0,0 +1,379 @@
+//! Builder style thread creation with join handles
+
+use core::cell::UnsafeCell;
+use core::ffi::CStr;
+use core::marker::PhantomData;
+
+use super::creation::{create_raw, CountingThreadScope, TrackedThread};
+use super::KernelPID;
+use crate::error::{NegativeErrorExt, NumericError};
+use crate::libc;
+
+/// Priority setting of a [Builder]
+#[derive(Debug, Copy, Clone)]
+enum Priority {
+    Absolute(u8),
+    Relative(i8),
+}
+
+/// Configuration for a new thread
+///
+/// Unlike [`spawn()`](super::spawn) and [`CountingThreadScope::spawn()`], this takes the
+/// thread's closure by value, and the closure can return a value that is obtained by joining the
+/// thread.
+///
+/// ```
+/// # #![no_std]
+/// # #![feature(start)]
+/// # #[start]
+/// # fn main(_argc: isize, _argv: *const *const u8) -> isize { panic!("Doc tests are not supposed to be run") }
+/// # use riot_wrappers::thread;
+/// let mut stack = [0u8; 2048];
+/// let sum = thread::scope(|scope| {
+///     let handle = thread::Builder::new()
+///         .name(c"adder")
+///         .priority_relative(-1)
+///         .spawn_scoped(scope, &mut stack, || 1 + 1)
+///         .unwrap();
+///     scope.join(handle)
+/// });
+/// ```
+///
+/// ## Stack usage
+///
+/// The closure and the space for the returned value are placed at the start of the stack memory
+/// that is passed in; the rest is used as the thread's stack.
+#[derive(Debug, Clone)]
+pub struct Builder {
+    name: &'static CStr,
+    priority: Priority,
+    flags: i32,
+}
+
+impl Builder {
+    /// Create a configuration for a thread that runs at the same priority as the current one.
+    pub fn new() -> Self {
+        Builder {
+            name: c"rust",
+            priority: Priority::Relative(0),
+            flags: 0,
+        }
+    }
+
+    /// Set the thread's human readable name (ignored in no-DEVHELP mode)
+    pub fn name(self, name: &'static CStr) -> Self {
+        Builder { name, ..self }
+    }
+
+    /// Set the thread's priority
+    ///
+    /// As usual in RIOT, lower numbers indicate more urgent threads.
+    pub fn priority(self, priority: u8) -> Self {
+        Builder {
+            priority: Priority::Absolute(priority),
+            ..self
+        }
+    }
+
+    /// Set the thread's priority relative to the thread that spawns it
+    ///
+    /// For example, -1 makes the new thread more urgent than the current one. The result is
+    /// limited to the valid priority range.
+    pub fn priority_relative(self, offset: i8) -> Self {
+        Builder {
+            priority: Priority::Relative(offset),
+            ..self
+        }
+    }
+
+    fn flag(self, flag: u32, enabled: bool) -> Self {
+        let flags = if enabled {
+            self.flags | flag as i32
+        } else {
+            self.flags & !(flag as i32)
+        };
+        Builder { flags, ..self }
+    }
+
+    /// Write markers into the stack to allow measuring stack usage later
+    #[doc(alias = "THREAD_CREATE_STACKTEST")]
+    pub fn stacktest(self, enabled: bool) -> Self {
+        self.flag(riot_sys::THREAD_CREATE_STACKTEST, enabled)
+    }
+
+    /// Create the thread in sleeping state, so that it only starts running when
+    /// [woken up](KernelPID::wakeup)
+    #[doc(alias = "THREAD_CREATE_SLEEPING")]
+    pub fn sleeping(self, enabled: bool) -> Self {
+        self.flag(riot_sys::THREAD_CREATE_SLEEPING, enabled)
+    }
+
+    fn resolved_priority(&self) -> u8 {
+        match self.priority {
+            Priority::Absolute(priority) => priority,
+            Priority::Relative(offset) => {
+                let current = super::get_pid()
+                    .priority()
+                    .unwrap_or(riot_sys::THREAD_PRIORITY_MAIN as _);
+                (i16::from(current) + i16::from(offset))
+                    .clamp(0, riot_sys::SCHED_PRIO_LEVELS as i16 - 1) as u8
+            }
+        }
+    }
+
+    /// Start a thread with a statically allocated stack
+    pub fn spawn<F, R>(
+        self,
+        stack: &'static mut [u8],
+        closure: F,
+    ) -> Result<JoinHandle<R>, NumericError>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        // unsafe: Everything is 'static
+        let (thread, slot) = unsafe { self.launch(stack, closure) }?;
+        Ok(JoinHandle { thread, slot })
+    }
+
+    /// Start a thread inside a [scope](super::scope), allowing it to use shorter lived data
+    ///
+    /// The thread needs to be [joined](CountingThreadScope::join) before the scope ends.
+    pub fn spawn_scoped<'env, 'id, F, R>(
+        self,
+        scope: &mut CountingThreadScope<'env, 'id>,
+        stack: &'env mut [u8],
+        closure: F,
+    ) -> Result<ScopedJoinHandle<'env, 'id, R>, NumericError>
+    where
+        F: FnOnce() -> R + Send + 'env,
+        R: Send + 'env,
+    {
+        // unsafe: The scope ensures that the thread is joined before anything that lives for 'env
+        // is released.
+        let (thread, slot) = unsafe { self.launch(stack, closure) }?;
+        scope.threads = scope.threads.checked_add(1).expect("Thread limit exceeded");
+        Ok(ScopedJoinHandle {
+            thread,
+            slot,
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Place the closure at the start of the stack and start the thread on the rest.
+    ///
+    /// The caller needs to ensure that the thread has terminated before 'a ends (joining it
+    /// through the slot suffices).
+    unsafe fn launch<'a, F, R>(
+        self,
+        stack: &'a mut [u8],
+        closure: F,
+    ) -> Result<(TrackedThread, &'a JoinSlot<R>), NumericError>
+    where
+        F: FnOnce() -> R + Send + 'a,
+        R: Send + 'a,
+    {
+        unsafe extern "C" fn run<F, R>(arg: *mut libc::c_void) -> *mut libc::c_void
+        where
+            F: FnOnce() -> R + Send,
+            R: Send,
+        {
+            // unsafe: Created from that type in launch, and valid until joined
+            let carved = unsafe { &*(arg as *const Carved<F, R>) };
+            // unsafe: Only accessed here after launch
+            let closure = unsafe { &mut *carved.closure.get() }
+                .take()
+                .expect("Thread closure is only run once");
+            let result = closure();
+            // unsafe: Only accessed here before the unlock, and by the joiner after it
+            unsafe { *carved.slot.result.get() = Some(result) };
+            // Interrupts stay disabled until the thread is terminated, so that on most platforms,
+            // a joining thread of higher priority does not even run before this thread is done.
+            // Where the unlock switches threads right away nonetheless (eg. on native), the
+            // joiner waits for the thread to be stopped before it releases the stack.
+            unsafe {
+                riot_sys::irq_disable();
+                riot_sys::mutex_unlock(crate::inline_cast_mut(carved.slot.done.get()));
+            }
+            core::ptr::null_mut()
+        }
+
+        let offset = stack
+            .as_ptr()
+            .align_offset(core::mem::align_of::<Carved<F, R>>());
+        let Some(end) = offset
+            .checked_add(core::mem::size_of::<Carved<F, R>>())
+            .filter(|end| *end <= stack.len())
+        else {
+            return Err(crate::error::ENOMEM);
+        };
+        let (carved, stack) = stack.split_at_mut(end);
+        let carved = carved[offset..].as_mut_ptr() as *mut Carved<F, R>;
+        // unsafe: Space is reserved and aligned as per the above
+        unsafe {
+            carved.write(Carved {
+                slot: JoinSlot {
+                    done: UnsafeCell::new(riot_sys::macro_MUTEX_INIT()),
+                    result: UnsafeCell::new(None),
+                },
+                closure: UnsafeCell::new(Some(closure)),
+            })
+        };
+        // unsafe: Valid as just written
+        let carved = unsafe { &*carved };
+        // unsafe: Freshly initialized, nobody else has the mutex
+        unsafe { riot_sys::mutex_trylock(crate::inline_cast_mut(carved.slot.done.get())) };
+
+        let priority = self.resolved_priority();
+        // unsafe: The stack and the argument are valid for 'a, and the caller ensures that the
+        // thread is done by then.
+        let (pid, tcb) = unsafe {
+            create_raw(
+                stack,
+                run::<F, R>,
+                carved as *const Carved<F, R> as *mut _,
+                self.name,
+                priority,
+                self.flags,
+            )
+        };
+        pid.negative_to_error()?;
+
+        Ok((
+            TrackedThread {
+                pid: KernelPID(pid),
+                tcb,
+            },
+            &carved.slot,
+        ))
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Data placed at the start of a [Builder] created thread's stack memory
+#[repr(C)]
+struct Carved<F, R> {
+    // Must be the first field, as it is accessed by the join handles without knowing F
+    slot: JoinSlot<R>,
+    closure: UnsafeCell<Option<F>>,
+}
+
+/// Rendezvous point of a thread's end with the thread joining it
+struct JoinSlot<R> {
+    /// Locked until the result has been written
+    done: UnsafeCell<riot_sys::inline::mutex_t>,
+    result: UnsafeCell<Option<R>>,
+}
+
+impl<R> JoinSlot<R> {
+    /// Wait for the thread to end and take its result
+    ///
+    /// The thread needs to be the one that was launched with this slot.
+    fn join(&self, thread: &TrackedThread) -> R {
+        crate::thread::InThread::new().expect("Threads can only be joined from a thread context");
+        // unsafe: Initialized at launch; running in a thread as checked
+        unsafe { riot_sys::mutex_lock(crate::inline_cast_mut(self.done.get())) };
+        // The result is in place, but the thread may still be running on its stack
+        wait_for_end(thread);
+        // unsafe: The thread is done with the result, and the handle to it is consumed
+        unsafe { &mut *self.result.get() }
+            .take()
+            .expect("Thread result is only taken once")
+    }
+}
+
+/// Block until a thread that has handed over its result has stopped.
+///
+/// If the thread is less urgent than the current one, it is raised to the current thread's
+/// priority, so that it gets to run to its end.
+fn wait_for_end(thread: &TrackedThread) {
+    let own = super::get_pid().priority().expect("Current thread exists");
+    while !matches!(thread.status(), super::Status::Stopped) {
+        if let Some(tcb) = thread.tcb {
+            // unsafe: The TCB is in the thread's stack memory, which is not released before the
+            // join is complete; if the thread has stopped in the meantime, changing the priority
+            // has no effect.
+            if unsafe { (*tcb).priority } > own {
+                // unsafe: As above. This does not switch threads, as it only makes the thread as
+                // urgent as the current one.
+                unsafe { riot_sys::sched_change_priority(tcb, own) };
+            }
+        }
+        // unsafe: C function can be called at any time in a thread
+        unsafe { riot_sys::thread_yield() };
+    }
+}
+
+/// Handle to a thread started by [`Builder::spawn()`]
+///
+/// Dropping the handle detaches the thread (and discards its return value).
+#[derive(Debug)]
+pub struct JoinHandle<R: 'static> {
+    thread: TrackedThread,
+    slot: &'static JoinSlot<R>,
+}
+
+// unsafe: The slot is only accessed by the thread and through the consumed handle
+unsafe impl<R: Send> Send for JoinHandle<R> {}
+
+impl<R> JoinHandle<R> {
+    /// PID of the thread (which may be reused by another thread once the thread has ended)
+    pub fn pid(&self) -> KernelPID {
+        self.thread.pid()
+    }
+
+    /// Block until the thread has ended, and return the closure's result.
+    ///
+    /// ## Panics
+    ///
+    /// ... when called from an interrupt.
+    pub fn join(self) -> R {
+        self.slot.join(&self.thread)
+    }
+}
+
+/// Handle to a thread started by [`Builder::spawn_scoped()`]
+///
+/// It needs to be passed to the scope's [`.join()`](CountingThreadScope::join) method before the
+/// scope ends.
+#[derive(Debug)]
+pub struct ScopedJoinHandle<'env, 'id, R> {
+    thread: TrackedThread,
+    slot: &'env JoinSlot<R>,
+    _phantom: PhantomData<&'id ()>,
+}
+
+// unsafe: The slot is only accessed by the thread and through the consumed handle
+unsafe impl<'env, 'id, R: Send> Send for ScopedJoinHandle<'env, 'id, R> {}
+
+impl<'env, 'id, R> ScopedJoinHandle<'env, 'id, R> {
+    /// PID of the thread (which may be reused by another thread once the thread has ended)
+    pub fn pid(&self) -> KernelPID {
+        self.thread.pid()
+    }
+}
+
+impl<'env, 'id> CountingThreadScope<'env, 'id> {
+    /// Block until the thread has ended, remove it from the list of pending threads in this
+    /// context, and return the thread closure's result.
+    ///
+    /// ## Panics
+    ///
+    /// ... when called from an interrupt.
+    pub fn join<R>(&mut self, thread: ScopedJoinHandle<'env, 'id, R>) -> R {
+        let result = thread.slot.join(&thread.thread);
+        self.threads -= 1;
+        result
+    }
+}
+
+impl<R> core::fmt::Debug for JoinSlot<R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("JoinSlot").finish_non_exhaustive()
+    }
+}
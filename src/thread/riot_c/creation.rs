@@ -34,13 +34,36 @@ where
         0 as *mut libc::c_void
     }
 
+    create_raw(
+        stack,
+        run::<R>,
+        closure as *mut R as *mut _,
+        name,
+        priority,
+        flags,
+    )
+}
+
+/// Even more internal helper that starts a thread at an arbitrary C function with an arbitrary
+/// argument.
+///
+/// The caller needs to ensure that the argument is valid for as long as the thread uses it, and
+/// that the stack outlives the thread; see [create] for the return value.
+pub(super) unsafe fn create_raw(
+    stack: &mut [u8],
+    function: unsafe extern "C" fn(*mut libc::c_void) -> *mut libc::c_void,
+    arg: *mut libc::c_void,
+    name: &CStr,
+    priority: u8,
+    flags: i32,
+) -> (raw::kernel_pid_t, Option<*mut riot_sys::thread_t>) {
     let pid = raw::thread_create(
         transmute(stack.as_mut_ptr()),
         stack.len() as i32,
         priority,
         flags,
-        Some(run::<R>),
-        closure as *mut R as *mut _,
+        Some(function),
+        arg,
         name.as_ptr() as _,
     );
 
@@ -65,6 +88,9 @@ where
 /// has no dynamic memory of the spawned threads, and no actual way of waiting for a thread. If the
 /// callback returns, the caller has call the scope's `.reap()` method with all the threads that
 /// were launched; otherwise, the program panics.
+///
+/// Threads started through [`Builder::spawn_scoped()`](super::Builder::spawn_scoped) are instead
+/// waited for using the scope's `.join()` method, which also produces the thread's return value.
 pub fn scope<'env, F, R>(callback: F) -> R
 where
     F: for<'id> FnOnce(&mut CountingThreadScope<'env, 'id>) -> R,
@@ -108,7 +134,7 @@ where
 /// Paper](http://plv.mpi-sws.org/rustbelt/ghostcell/paper.pdf).
 ///
 pub struct CountingThreadScope<'env, 'id> {
-    threads: u16, // a counter, but larger than kernel_pid_t
+    pub(super) threads: u16, // a counter, but larger than kernel_pid_t
     _phantom: PhantomData<(&'env (), &'id ())>,
 }
 
@@ -225,9 +251,9 @@ where
 /// tcb (although that'll need to become a lifetime'd reference to a cell by then).
 #[derive(Debug)]
 pub struct TrackedThread {
-    pid: KernelPID,
+    pub(super) pid: KernelPID,
     // If this is None, then the thread was so short-lived that the TCB couldn't even be extracted
-    tcb: Option<*mut riot_sys::thread_t>,
+    pub(super) tcb: Option<*mut riot_sys::thread_t>,
 }
 
 impl TrackedThread {
@@ -0,0 +1,246 @@
+//! Hooks into the scheduler through RIOT's [sched_cb] module
+//!
+//! A [Hook] registered with [register()] is called on every context switch, with the PIDs of the
+//! thread that was running (if any) and of the thread that is about to run.
+//!
+//! The hook runs inside the scheduler, with interrupts disabled. It should be short, and must not
+//! block or otherwise interact with the scheduler. For debugging, the [Tracer] records switches
+//! into a ring buffer that can be dumped later, which perturbs timing much less than printing on
+//! the spot would.
+//!
+//! RIOT supports only a single scheduler callback. Registering a hook replaces any callback that
+//! was registered before. The one of `schedstatistics` (which is the only C module in RIOT that
+//! registers one) is called by the registered hook before the hook itself, and is restored when
+//! the hook is unregistered.
+//!
+//! ## Example
+//!
+//! ```
+//! # #![no_std]
+//! # #![feature(start)]
+//! # #[start]
+//! # fn main(_argc: isize, _argv: *const *const u8) -> isize { panic!("Doc tests are not supposed to be run") }
+//! # use riot_wrappers::thread::sched_cb::{register, Tracer};
+//! // The tracer is typically placed in a static_cell::StaticCell, and created with
+//! // `Tracer::new(Clock::msec().acquire())`
+//! fn trace_while(tracer: &'static mut Tracer<1000, 32>, f: impl FnOnce()) {
+//!     let registration = register(tracer);
+//!     f();
+//!     let tracer = registration.unregister();
+//!     tracer.dump();
+//! }
+//! ```
+//!
+//! [sched_cb]: https://doc.riot-os.org/group__core__sched.html
+
+use core::cell::Cell;
+use core::marker::PhantomData;
+
+use super::KernelPID;
+
+/// Something that can be called on every context switch
+///
+/// This is implemented for all suitable closures.
+pub trait Hook: Send {
+    /// Called by the scheduler when switching from the `prev` thread (if any was running) to the
+    /// `next` thread
+    fn switch(&mut self, prev: Option<KernelPID>, next: KernelPID);
+}
+
+impl<F: FnMut(Option<KernelPID>, KernelPID) + Send> Hook for F {
+    fn switch(&mut self, prev: Option<KernelPID>, next: KernelPID) {
+        self(prev, next)
+    }
+}
+
+/// Address of the currently registered hook, or 0 (type erased, the trampoline knows its type)
+static HOOK: bare_metal::Mutex<Cell<usize>> = bare_metal::Mutex::new(Cell::new(0));
+
+/// Callback of a C module that is replaced by registering a hook, and thus called by the hook's
+/// trampoline and registered again when the hook is unregistered
+#[cfg(riot_module_schedstatistics)]
+const CHAINED: riot_sys::sched_callback_t = Some(riot_sys::sched_statistics_cb);
+#[cfg(not(riot_module_schedstatistics))]
+const CHAINED: riot_sys::sched_callback_t = None;
+
+extern "C" fn trampoline<H: Hook>(active: riot_sys::kernel_pid_t, next: riot_sys::kernel_pid_t) {
+    if let Some(chained) = CHAINED {
+        // unsafe: Called from the scheduler just like when it was registered itself
+        unsafe { chained(active, next) };
+    }
+    let hook = crate::interrupt::free(|cs| HOOK.borrow(cs).get());
+    if hook == 0 {
+        // Unregistered while the scheduler was about to call us
+        return;
+    }
+    // unsafe: The pointer was created from a `&'static mut H` in register() together with
+    // installing this very trampoline, and is only accessed here (where the scheduler does not
+    // run concurrently) or inside a critical section.
+    let hook = unsafe { &mut *(hook as *mut H) };
+    // The scheduler always switches to a valid thread; if not, there is nothing to report.
+    if let Some(next) = KernelPID::new(next) {
+        hook.switch(KernelPID::new(active), next);
+    }
+}
+
+/// Register a hook to be called on every context switch.
+///
+/// Any previously registered hook is replaced; a [Registration] of a hook that has been replaced
+/// still gives back its hook on [`.unregister()`](Registration::unregister), but does not affect
+/// the newer hook. The callback of `schedstatistics` keeps being called (see the [module
+/// documentation](self)).
+#[doc(alias = "sched_register_cb")]
+pub fn register<H: Hook>(hook: &'static mut H) -> Registration<H> {
+    let hook = hook as *mut H;
+    crate::interrupt::free(|cs| {
+        HOOK.borrow(cs).set(hook as usize);
+        // unsafe: C function can be called at any time; the trampoline is valid for the hook that
+        // was just stored.
+        unsafe { riot_sys::sched_register_cb(Some(trampoline::<H> as _)) };
+    });
+    Registration {
+        hook,
+        _phantom: PhantomData,
+    }
+}
+
+/// A registered scheduler [Hook]
+///
+/// Dropping this leaves the hook registered forever.
+#[must_use = "Dropping the registration leaves the hook registered forever"]
+pub struct Registration<H: Hook> {
+    hook: *mut H,
+    // Not Send as the hook may be accessed with interrupts disabled, but not from other threads
+    _phantom: PhantomData<&'static mut H>,
+}
+
+impl<H: Hook> Registration<H> {
+    /// Run a function on the hook while it can not be called by the scheduler.
+    ///
+    /// Interrupts are disabled while the function runs, so it should be short.
+    pub fn with<R>(&mut self, f: impl FnOnce(&mut H) -> R) -> R {
+        crate::interrupt::free(|_| {
+            // unsafe: The hook is only accessed by the trampoline, which does not run while
+            // interrupts are disabled.
+            f(unsafe { &mut *self.hook })
+        })
+    }
+
+    /// Stop calling the hook, and give it back.
+    pub fn unregister(self) -> &'static mut H {
+        crate::interrupt::free(|cs| {
+            let current = HOOK.borrow(cs);
+            if current.get() == self.hook as usize {
+                current.set(0);
+                // unsafe: C function can be called at any time
+                unsafe { riot_sys::sched_register_cb(CHAINED) };
+            }
+        });
+        // unsafe: The hook came from a `&'static mut`, and is not referenced from the scheduler
+        // any more.
+        unsafe { &mut *self.hook }
+    }
+}
+
+impl<H: Hook> core::fmt::Debug for Registration<H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Registration<{}>", core::any::type_name::<H>())
+    }
+}
+
+/// A context switch recorded by a [Tracer]
+#[derive(Debug, Copy, Clone)]
+#[cfg(riot_module_ztimer)]
+pub struct TraceEntry<const HZ: u32> {
+    /// When the switch happened
+    pub time: crate::ztimer::Timestamp<HZ>,
+    /// Thread that was running before (if any)
+    pub prev: Option<KernelPID>,
+    /// Thread that runs after the switch
+    pub next: KernelPID,
+}
+
+/// A [Hook] that records the last `N` context switches with time stamps from a clock of
+/// frequency `HZ`
+#[cfg(riot_module_ztimer)]
+pub struct Tracer<const HZ: u32, const N: usize> {
+    clock: crate::ztimer::LockedClock<HZ>,
+    entries: [Option<TraceEntry<HZ>>; N],
+    // Position the next entry is written to
+    cursor: usize,
+    // Number of switches ever recorded
+    recorded: usize,
+}
+
+// unsafe: The clock is only used to read its time, which ztimer allows from any context
+#[cfg(riot_module_ztimer)]
+unsafe impl<const HZ: u32, const N: usize> Send for Tracer<HZ, N> {}
+
+#[cfg(riot_module_ztimer)]
+impl<const HZ: u32, const N: usize> Tracer<HZ, N> {
+    /// Create an empty tracer
+    ///
+    /// The clock is kept acquired for the tracer's lifetime, so that time stamps are meaningful.
+    pub fn new(clock: crate::ztimer::LockedClock<HZ>) -> Self {
+        Tracer {
+            clock,
+            entries: [None; N],
+            cursor: 0,
+            recorded: 0,
+        }
+    }
+
+    /// Number of context switches recorded since creation or the last [`.clear()`](Self::clear)
+    ///
+    /// This includes those that were already overwritten in the buffer.
+    pub fn recorded(&self) -> usize {
+        self.recorded
+    }
+
+    /// Iterate over the recorded switches that are still in the buffer, oldest first
+    pub fn entries(&self) -> impl Iterator<Item = &TraceEntry<HZ>> {
+        let (newer, older) = self.entries.split_at(self.cursor);
+        older.iter().chain(newer.iter()).filter_map(|e| e.as_ref())
+    }
+
+    /// Forget all recorded switches
+    pub fn clear(&mut self) {
+        self.entries = [None; N];
+        self.cursor = 0;
+        self.recorded = 0;
+    }
+
+    /// Print the recorded switches to stdio
+    pub fn dump(&self) {
+        let kept = self.entries().count();
+        crate::println!(
+            "{} context switches recorded, showing last {}:",
+            self.recorded,
+            kept
+        );
+        for entry in self.entries() {
+            crate::println!(
+                "{:>10} {:?} -> {:?}",
+                entry.time.0,
+                entry.prev.map(|p| p.0),
+                entry.next.0
+            );
+        }
+    }
+}
+
+#[cfg(riot_module_ztimer)]
+impl<const HZ: u32, const N: usize> Hook for Tracer<HZ, N> {
+    fn switch(&mut self, prev: Option<KernelPID>, next: KernelPID) {
+        if N == 0 {
+            return;
+        }
+        self.entries[self.cursor] = Some(TraceEntry {
+            time: self.clock.now(),
+            prev,
+            next,
+        });
+        self.cursor = (self.cursor + 1) % N;
+        self.recorded = self.recorded.wrapping_add(1);
+    }
+}
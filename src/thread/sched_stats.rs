@@ -0,0 +1,192 @@
+//! Per-thread scheduling statistics, as gathered by the `schedstatistics` module
+
+use super::{KernelPID, NoSuchThread};
+use crate::ztimer::Ticks;
+
+/// Scheduling information about a thread, returned by [KernelPID::sched_stats()].
+///
+/// The runtime is measured in microseconds. As a [Ticks] value, it wraps around (after about 71
+/// minutes); the full value is available through [`.runtime_us()`](Self::runtime_us).
+/// Differences between two measurements can be obtained conveniently through [SchedSnapshot].
+#[derive(Debug, Copy, Clone)]
+#[non_exhaustive]
+pub struct SchedStats {
+    pub(crate) schedules: u32,
+    pub(crate) runtime_us: u64,
+}
+
+impl SchedStats {
+    /// Number of times the thread was scheduled to run
+    pub fn schedules(&self) -> u32 {
+        self.schedules
+    }
+
+    /// Accumulated time the thread was running, including its ongoing time slice if it is the
+    /// currently running thread
+    ///
+    /// The value wraps around after about 71 minutes.
+    pub fn runtime(&self) -> Ticks<1_000_000> {
+        // Truncating is the documented wrapping behavior
+        Ticks(self.runtime_us as u32)
+    }
+
+    /// Accumulated time the thread was running, in microseconds
+    ///
+    /// Unlike [`.runtime()`](Self::runtime), this does not wrap around in practice.
+    pub fn runtime_us(&self) -> u64 {
+        self.runtime_us
+    }
+}
+
+impl KernelPID {
+    /// Gather scheduling statistics about the thread.
+    #[doc(alias = "sched_pidlist")]
+    pub fn sched_stats(&self) -> Result<SchedStats, NoSuchThread> {
+        self.status()?;
+        // Disabling interrupts as the scheduler updates the fields, and the runtime may be wider
+        // than the platform's atomic accesses.
+        let (stat, ongoing) = crate::interrupt::free(|_| {
+            // unsafe: The list has an entry for every valid PID, and is only modified by the
+            // scheduler, which does not run while interrupts are disabled.
+            let stat = unsafe {
+                core::ptr::addr_of!(riot_sys::sched_pidlist[self.0 as usize]).read_volatile()
+            };
+            // The scheduler only accounts for a time slice when it ends
+            let ongoing = if super::get_pid() == *self {
+                // unsafe: The clock is the one schedstatistics measures with, and is kept running
+                // by it
+                let now = unsafe {
+                    riot_sys::inline::ztimer_now(crate::inline_cast_mut(riot_sys::ZTIMER_USEC))
+                } as u32;
+                now.wrapping_sub(stat.laststart)
+            } else {
+                0
+            };
+            (stat, ongoing)
+        });
+        Ok(SchedStats {
+            schedules: stat.schedules as _,
+            runtime_us: u64::from(stat.runtime_us) + u64::from(ongoing),
+        })
+    }
+}
+
+/// Scheduling statistics of all threads at one point in time
+///
+/// Two snapshots can be compared to see how the CPU time was shared in between:
+///
+/// ```
+/// # #![no_std]
+/// # #![feature(start)]
+/// # #[start]
+/// # fn main(_argc: isize, _argv: *const *const u8) -> isize { panic!("Doc tests are not supposed to be run") }
+/// # use riot_wrappers::thread::SchedSnapshot;
+/// # use riot_wrappers::ztimer::{Clock, Ticks};
+/// # use riot_wrappers::println;
+/// let before = SchedSnapshot::take();
+/// Clock::msec().sleep(Ticks(1000));
+/// let after = SchedSnapshot::take();
+/// for usage in after.since(&before) {
+///     println!(
+///         "{:?}: {} per mille",
+///         usage.pid().get_name(),
+///         usage.permille()
+///     );
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SchedSnapshot {
+    // Indexed by PID; None where no thread was running
+    stats: [Option<SchedStats>; riot_sys::KERNEL_PID_LAST as usize + 1],
+}
+
+impl SchedSnapshot {
+    /// Gather the statistics of all threads that currently exist
+    pub fn take() -> Self {
+        let mut stats = [None; riot_sys::KERNEL_PID_LAST as usize + 1];
+        for pid in KernelPID::all_pids() {
+            stats[pid.0 as usize] = pid.sched_stats().ok();
+        }
+        SchedSnapshot { stats }
+    }
+
+    /// Statistics of a thread at the time of the snapshot, if it existed then
+    pub fn get(&self, pid: KernelPID) -> Option<SchedStats> {
+        self.stats[pid.0 as usize]
+    }
+
+    /// Usage of each thread that existed at both snapshots between an earlier snapshot and this
+    ///
+    /// Threads that were started or stopped in between are not reported, and their CPU time is
+    /// not counted in the total.
+    pub fn since<'a>(
+        &'a self,
+        earlier: &'a SchedSnapshot,
+    ) -> impl Iterator<Item = ThreadUsage> + 'a {
+        let differences = move || {
+            KernelPID::all_pids().filter_map(move |pid| {
+                let now = self.get(pid)?;
+                let then = earlier.get(pid)?;
+                Some((
+                    pid,
+                    now.schedules.wrapping_sub(then.schedules),
+                    // Only a reused PID could go backwards
+                    now.runtime_us.saturating_sub(then.runtime_us),
+                ))
+            })
+        };
+        let total = differences().map(|(_, _, runtime)| runtime).sum::<u64>();
+        differences().map(move |(pid, schedules, runtime_us)| ThreadUsage {
+            pid,
+            schedules,
+            runtime_us,
+            total,
+        })
+    }
+}
+
+/// How much a thread ran between two [SchedSnapshot]s
+#[derive(Debug, Copy, Clone)]
+pub struct ThreadUsage {
+    pid: KernelPID,
+    schedules: u32,
+    runtime_us: u64,
+    // Sum of all threads' runtime
+    total: u64,
+}
+
+impl ThreadUsage {
+    /// The thread this is about
+    pub fn pid(&self) -> KernelPID {
+        self.pid
+    }
+
+    /// Number of times the thread was scheduled to run in that time
+    pub fn schedules(&self) -> u32 {
+        self.schedules
+    }
+
+    /// Time the thread was running
+    ///
+    /// The value wraps around if the snapshots were taken more than about 71 minutes apart.
+    pub fn runtime(&self) -> Ticks<1_000_000> {
+        // Truncating is the documented wrapping behavior
+        Ticks(self.runtime_us as u32)
+    }
+
+    /// Time the thread was running, in microseconds
+    pub fn runtime_us(&self) -> u64 {
+        self.runtime_us
+    }
+
+    /// Share of the CPU time used by this thread, in thousandths
+    ///
+    /// As all threads (including the idle thread, if there is one) are considered, these add up
+    /// to about 1000.
+    pub fn permille(&self) -> u32 {
+        if self.total == 0 {
+            return 0;
+        }
+        (self.runtime_us * 1000 / self.total) as u32
+    }
+}
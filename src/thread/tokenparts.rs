@@ -31,8 +31,6 @@ pub struct EndToken {
 /// * `MSG_SEMANTICS`: If this is true, the thread has not assigned semantics to messages it would receive yet.
 /// * `MSG_QUEUE`: If this is true, the thread has not yet set up a message queue.
 /// * `FLAG_SEMANTICS`: If this is true, the thread has not assigned semantics to flags yet.
-///
-/// (FLAG_SEMANTICS are not used yet, but are already prepared for a wrapper similar to `msg::v2`).
 pub struct TokenParts<const MSG_SEMANTICS: bool, const MSG_QUEUE: bool, const FLAG_SEMANTICS: bool>
 {
     pub(super) _not_send: PhantomData<*const ()>,
@@ -116,6 +114,46 @@ impl<const MQ: bool, const FS: bool> TokenParts<false, MQ, FS> {
     }
 }
 
+impl<const MS: bool, const MQ: bool> TokenParts<MS, MQ, true> {
+    /// Extract the claim that the thread has not assigned any meaning to its thread flags yet.
+    ///
+    /// The resulting [`FlagSemantics`](super::flags::FlagSemantics) can be used to create pairs
+    /// of flag senders and receivers; see the [flags module](super::flags) for an example.
+    #[cfg(riot_module_core_thread_flags)]
+    pub fn take_flag_semantics(self) -> (TokenParts<MS, MQ, false>, super::flags::FlagSemantics) {
+        (
+            TokenParts {
+                _not_send: PhantomData,
+            },
+            // unsafe: As with take_msg_semantics, this runs only once per thread, and the thread
+            // can't produce an end token before the semantics are returned.
+            unsafe { super::flags::FlagSemantics::new() },
+        )
+    }
+}
+
+impl<const MS: bool, const MQ: bool> TokenParts<MS, MQ, false> {
+    /// Inverse of [TokenParts::take_flag_semantics], indicating that the thread may be terminated
+    /// again as far as flag semantics are concerned.
+    ///
+    /// # Panics
+    ///
+    /// ... if not all flag senders and receivers have been returned to the semantics.
+    #[cfg(riot_module_core_thread_flags)]
+    pub fn return_flag_semantics(
+        self,
+        semantics: super::flags::FlagSemantics,
+    ) -> TokenParts<MS, MQ, true> {
+        assert!(
+            semantics.is_complete(),
+            "Flag semantics can only be returned when all flags were returned"
+        );
+        TokenParts {
+            _not_send: PhantomData,
+        }
+    }
+}
+
 impl<const MS: bool, const FS: bool> TokenParts<MS, true, FS> {
     /// Set up a message queue of given size N, and run the function `f` after that has been set
     /// up. `f` gets passed all the remaining thread invariants.
@@ -0,0 +1,212 @@
+//! 64-bit clocks built on RIOT's [ztimer64](https://doc.riot-os.org/group__sys__ztimer64.html)
+//!
+//! These mirror the 32-bit [Clock](super::Clock), [Ticks](super::Ticks) and
+//! [Timestamp](super::Timestamp), but as their values do not wrap around in any practical time
+//! span (not even on a microsecond clock), their time stamps can be compared and logged across
+//! the whole uptime of the system.
+
+use super::{Overflow, NANOS_PER_SEC};
+use crate::thread::{InThread, ValueInThread};
+
+/// A 64-bit clock that knows about its frequency
+///
+/// Unlike with a [Clock](super::Clock), its current time is always available through
+/// [`.now64()`](Self::now64), as ztimer64 keeps counting for as long as the system runs.
+#[derive(Copy, Clone)]
+pub struct Clock64<const HZ: u32>(pub(crate) *mut riot_sys::ztimer64_clock_t);
+
+/// A duration on a 64-bit clock of fixed speed
+///
+/// In memory, these are numbers of ticks. Semantically, these are durations of `self.0 / HZ`
+/// seconds.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Ticks64<const HZ: u32>(pub u64);
+
+/// A time on a 64-bit clock ticking at a fixed speed
+///
+/// Time stamps of the global clocks count from the time the clock was started (typically at
+/// boot). As they do not wrap around, time stamps from the same clock can be compared directly.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Timestamp64<const HZ: u32>(pub u64);
+
+impl<const HZ: u32> Clock64<HZ> {
+    /// Get the current time value of the clock.
+    ///
+    /// This can be used in any context, including interrupts.
+    #[doc(alias = "ztimer64_now")]
+    pub fn now64(&self) -> Timestamp64<HZ> {
+        // unsafe: C function can be called at any time on a valid clock
+        Timestamp64(unsafe { riot_sys::ztimer64_now(self.0) })
+    }
+}
+
+impl<const HZ: u32> ValueInThread<Clock64<HZ>> {
+    /// Pause the current thread until the clock reaches the given time.
+    ///
+    /// If that time has already passed, this returns immediately.
+    #[doc(alias = "ztimer64_sleep_until")]
+    pub fn sleep_until(&self, target: Timestamp64<HZ>) {
+        // unsafe: Running in a thread as per ValueInThread, and the clock is valid
+        unsafe { riot_sys::ztimer64_sleep_until(self.0, target.0) };
+    }
+
+    /// Pause the current thread for the duration of ticks in the timer's time scale.
+    #[doc(alias = "ztimer64_sleep")]
+    pub fn sleep(&self, duration: Ticks64<HZ>) {
+        self.sleep_until(self.now64() + duration);
+    }
+}
+
+impl Clock64<1> {
+    /// Get the global 64-bit second clock, ZTIMER64_SEC.
+    ///
+    /// This function verifies (at a small runtime cost) that the caller is in a thread context.
+    /// This can be avoided by calling `in_thread.promote(Clock64::sec_unbound())` on an existing
+    /// [crate::thread::InThread] token.
+    #[cfg(riot_module_ztimer64_sec)]
+    #[doc(alias = "ZTIMER64_SEC")]
+    pub fn sec() -> ValueInThread<Self> {
+        InThread::new()
+            .expect("Thread-bound ZTimer clock created in ISR")
+            .promote(Self::sec_unbound())
+    }
+
+    /// Get the global 64-bit second clock, ZTIMER64_SEC.
+    ///
+    /// The clock is *not* packed in a [ValueInThread], which makes the blocking sleep methods
+    /// unavailable, but works even in interrupts contexts.
+    #[cfg(riot_module_ztimer64_sec)]
+    pub fn sec_unbound() -> Self {
+        Clock64(unsafe { riot_sys::ZTIMER64_SEC })
+    }
+}
+
+impl Clock64<1000> {
+    /// Get the global 64-bit milliseconds clock, ZTIMER64_MSEC.
+    ///
+    /// This function verifies (at a small runtime cost) that the caller is in a thread context.
+    /// This can be avoided by calling `in_thread.promote(Clock64::msec_unbound())` on an existing
+    /// [crate::thread::InThread] token.
+    #[cfg(riot_module_ztimer64_msec)]
+    #[doc(alias = "ZTIMER64_MSEC")]
+    pub fn msec() -> ValueInThread<Self> {
+        InThread::new()
+            .expect("Thread-bound ZTimer clock created in ISR")
+            .promote(Self::msec_unbound())
+    }
+
+    /// Get the global 64-bit milliseconds clock, ZTIMER64_MSEC.
+    ///
+    /// The clock is *not* packed in a [ValueInThread], which makes the blocking sleep methods
+    /// unavailable, but works even in interrupts contexts.
+    #[cfg(riot_module_ztimer64_msec)]
+    pub fn msec_unbound() -> Self {
+        Clock64(unsafe { riot_sys::ZTIMER64_MSEC })
+    }
+}
+
+impl Clock64<1000000> {
+    /// Get the global 64-bit microseconds clock, ZTIMER64_USEC.
+    ///
+    /// This function verifies (at a small runtime cost) that the caller is in a thread context.
+    /// This can be avoided by calling `in_thread.promote(Clock64::usec_unbound())` on an existing
+    /// [crate::thread::InThread] token.
+    #[cfg(riot_module_ztimer64_usec)]
+    #[doc(alias = "ZTIMER64_USEC")]
+    pub fn usec() -> ValueInThread<Self> {
+        InThread::new()
+            .expect("Thread-bound ZTimer clock created in ISR")
+            .promote(Self::usec_unbound())
+    }
+
+    /// Get the global 64-bit microseconds clock, ZTIMER64_USEC.
+    ///
+    /// The clock is *not* packed in a [ValueInThread], which makes the blocking sleep methods
+    /// unavailable, but works even in interrupts contexts.
+    #[cfg(riot_module_ztimer64_usec)]
+    pub fn usec_unbound() -> Self {
+        Clock64(unsafe { riot_sys::ZTIMER64_USEC })
+    }
+}
+
+impl<const HZ: u32> Ticks64<HZ> {
+    /// Fallible conversion from a Duration
+    ///
+    /// The result is rounded up if the duration is not a multiple of the clock's tick length; it
+    /// is exact for all clocks whose frequency is a divisor of $10^9$.
+    ///
+    /// This is an extra function (equivalently available as try_from) as it allows the result to
+    /// be const.
+    pub const fn from_duration(duration: core::time::Duration) -> Result<Self, Overflow> {
+        let subsec_ticks =
+            (duration.subsec_nanos() as u64 * HZ as u64).div_ceil(NANOS_PER_SEC as u64);
+        let sec_ticks = match duration.as_secs().checked_mul(HZ as u64) {
+            Some(s) => s,
+            _ => return Err(Overflow),
+        };
+        match sec_ticks.checked_add(subsec_ticks) {
+            Some(t) => Ok(Ticks64(t)),
+            _ => Err(Overflow),
+        }
+    }
+
+    /// Conversion into a Duration
+    ///
+    /// This is exact for all clocks whose frequency is a divisor of $10^9$, and rounds down
+    /// otherwise. As a Duration can express more seconds than a 64-bit clock of at least 1 Hz can
+    /// count, this can not overflow.
+    pub const fn to_duration(self) -> core::time::Duration {
+        let secs = self.0 / HZ as u64;
+        let subsec_ticks = self.0 % HZ as u64;
+        let nanos = subsec_ticks * NANOS_PER_SEC as u64 / HZ as u64;
+        core::time::Duration::new(secs, nanos as u32)
+    }
+}
+
+impl<const HZ: u32> TryFrom<core::time::Duration> for Ticks64<HZ> {
+    type Error = Overflow;
+
+    fn try_from(duration: core::time::Duration) -> Result<Self, Overflow> {
+        Self::from_duration(duration)
+    }
+}
+
+impl<const HZ: u32> From<Ticks64<HZ>> for core::time::Duration {
+    fn from(ticks: Ticks64<HZ>) -> Self {
+        ticks.to_duration()
+    }
+}
+
+impl<const HZ: u32> From<super::Ticks<HZ>> for Ticks64<HZ> {
+    fn from(ticks: super::Ticks<HZ>) -> Self {
+        Ticks64(ticks.0.into())
+    }
+}
+
+impl<const HZ: u32> Timestamp64<HZ> {
+    /// Time passed since the clock started (typically, the system's uptime)
+    pub const fn since_start(self) -> core::time::Duration {
+        Ticks64::<HZ>(self.0).to_duration()
+    }
+}
+
+impl<const HZ: u32> core::ops::Sub for Timestamp64<HZ> {
+    type Output = Ticks64<HZ>;
+
+    /// Time passed between two time stamps
+    ///
+    /// ## Panics
+    ///
+    /// ... in debug mode, if the other time stamp is later than self.
+    fn sub(self, other: Self) -> Ticks64<HZ> {
+        Ticks64(self.0 - other.0)
+    }
+}
+
+impl<const HZ: u32> core::ops::Add<Ticks64<HZ>> for Timestamp64<HZ> {
+    type Output = Timestamp64<HZ>;
+
+    fn add(self, duration: Ticks64<HZ>) -> Timestamp64<HZ> {
+        Timestamp64(self.0 + duration.0)
+    }
+}
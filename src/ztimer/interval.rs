@@ -0,0 +1,115 @@
+//! Fixed rate ticks for async code
+
+use super::{Clock, LockedClock, Ticks, Timestamp};
+
+/// A source of ticks at a fixed rate, for use in async code
+///
+/// This is created by [`Clock::interval()`]; its [`.tick()`](Self::tick) futures complete one
+/// period after the previous tick was due. As the ticks are calculated from when they were due
+/// (rather than when the task got around to awaiting the next one), the rate does not drift even
+/// if the task is delayed, as with RIOT's `ztimer_periodic_wakeup`.
+///
+/// Unlike a periodic timer (`ztimer::periodic::Timer`), this does not run any code in interrupt
+/// context, and needs no `'static` handler.
+///
+/// ```
+/// # #![no_std]
+/// # #![feature(start)]
+/// # #[start]
+/// # fn main(_argc: isize, _argv: *const *const u8) -> isize { panic!("Doc tests are not supposed to be run") }
+/// # use riot_wrappers::ztimer::{Clock, Ticks};
+/// # fn sample() -> u16 { 0 }
+/// # async {
+/// let mut interval = Clock::msec().interval(Ticks(100));
+/// loop {
+///     let missed = interval.tick().await;
+///     if missed > 0 {
+///         riot_wrappers::println!("Missed {} samples", missed);
+///     }
+///     riot_wrappers::println!("Sampled {}", sample());
+/// }
+/// # };
+/// ```
+///
+/// The clock is [acquired](Clock::acquire) for as long as the interval exists.
+pub struct Interval<const HZ: u32> {
+    clock: LockedClock<HZ>,
+    /// Time at which the last tick was due (or at which the interval was started or reset)
+    last: Timestamp<HZ>,
+    period: Ticks<HZ>,
+}
+
+impl<const HZ: u32> Clock<HZ> {
+    /// Create an interval that ticks every `period` ticks, starting one period from now.
+    ///
+    /// ## Panics
+    ///
+    /// ... if the period is zero.
+    #[doc(alias = "ztimer_periodic_wakeup")]
+    pub fn interval(&self, period: Ticks<HZ>) -> Interval<HZ> {
+        assert!(period.0 > 0, "Interval period must not be zero");
+        let clock = self.acquire();
+        let last = clock.now();
+        Interval {
+            clock,
+            last,
+            period,
+        }
+    }
+}
+
+impl<const HZ: u32> Interval<HZ> {
+    /// Wait for the next tick.
+    ///
+    /// If one or more ticks were already due when this is called (because the task did not get
+    /// around to waiting for them in time), this returns right away, and the number of ticks
+    /// that were skipped beyond the one this returns for. Time is measured on a 32-bit clock, so
+    /// falling behind by more than 2³² ticks is not detected.
+    pub async fn tick(&mut self) -> u32 {
+        let elapsed = self.clock.now() - self.last;
+        if elapsed.0 < self.period.0 {
+            self.clock
+                .0
+                .sleep_async(Ticks(self.period.0 - elapsed.0))
+                .await;
+            self.last = Timestamp(self.last.0.wrapping_add(self.period.0));
+            0
+        } else {
+            let due = elapsed.0 / self.period.0;
+            self.last = Timestamp(self.last.0.wrapping_add(due * self.period.0));
+            due - 1
+        }
+    }
+
+    /// Period between two ticks
+    pub fn period(&self) -> Ticks<HZ> {
+        self.period
+    }
+
+    /// Change the period between two ticks.
+    ///
+    /// The new period applies from the last tick on, so the next tick is due one new period
+    /// after the last one.
+    ///
+    /// ## Panics
+    ///
+    /// ... if the period is zero.
+    pub fn set_period(&mut self, period: Ticks<HZ>) {
+        assert!(period.0 > 0, "Interval period must not be zero");
+        self.period = period;
+    }
+
+    /// Start counting periods from now, discarding any ticks that are currently due.
+    pub fn reset(&mut self) {
+        self.last = self.clock.now();
+    }
+}
+
+impl<const HZ: u32> core::fmt::Debug for Interval<HZ> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Interval")
+            .field("last", &self.last)
+            .field("period", &self.period)
+            .finish()
+    }
+}
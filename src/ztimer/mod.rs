@@ -8,10 +8,30 @@
 //! methods take numeric tick counts and durations, not only for historical reasons, but also
 //! because sleeping for a Duration works infallibly (even if the duration exceeds the maximum
 //! number of ticks a timer can sleep) by sleeping in repetitions.
+//!
+//! With the `ztimer64` module, 64-bit clocks ([Clock64]) are available as well; their time stamps
+//! do not wrap around, and can thus be compared across the whole uptime.
+//!
+//! Futures can be limited in the time they take through [`Clock::timeout()`], or by a [Deadline]
+//! shared across several steps. An [Interval] provides ticks at a fixed rate to async code.
+//!
+//! Closures can be run in interrupt context after a delay through a [OneShot] timer.
 
 #[cfg(riot_module_ztimer_periodic)]
 pub mod periodic;
 
+mod interval;
+pub use interval::Interval;
+mod oneshot;
+pub use oneshot::{OneShot, ScopedOneShot};
+mod timeout;
+pub use timeout::{Deadline, Elapsed, Timeout};
+
+#[cfg(riot_module_ztimer64)]
+mod clock64;
+#[cfg(riot_module_ztimer64)]
+pub use clock64::{Clock64, Ticks64, Timestamp64};
+
 use core::convert::TryInto;
 use core::mem::ManuallyDrop;
 use core::pin::Pin;
@@ -29,7 +49,7 @@ const NANOS_PER_SEC: u32 = 1_000_000_000;
 /// as that's not yet supported by const generics, and because clock rates are often easier to
 /// express in Hertz than in multiples of 10^-n seconds.
 #[derive(Copy, Clone)]
-pub struct Clock<const HZ: u32>(*mut ztimer_clock_t);
+pub struct Clock<const HZ: u32>(pub(crate) *mut ztimer_clock_t);
 
 /// A [Clock] that has been acquired using [Clock::acquire()] (which is backed by
 /// [ztimer_acquire]). Times from a single acquired clock can be compared.
@@ -166,7 +186,7 @@ impl<const HZ: u32> Clock<HZ> {
 
     /// A version of [`ValueInThread<Clock>::set_during`] that relies on this module's knowledge of
     /// the circumstances to state the validity of its use even without a [`ValueInThread`]
-    fn set_during<I: FnOnce() + Send, M: FnOnce() -> R, R>(
+    pub(crate) fn set_during<I: FnOnce() + Send, M: FnOnce() -> R, R>(
         &self,
         callback: I,
         ticks: Ticks<HZ>,
@@ -644,7 +664,7 @@ impl<const HZ: u32> core::future::Future for AsyncSleep<HZ> {
 
             // Instead of doing this relatively costly check, might we instead atomically set a
             // property of the PendingTimer in the callback?
-            if unsafe { riot_sys::ztimer_is_set(riot_sys::ZTIMER_MSEC, &running.timer) != 0 } {
+            if unsafe { riot_sys::ztimer_is_set(running.clock.0, &running.timer) != 0 } {
                 core::task::Poll::Pending
             } else {
                 core::task::Poll::Ready(())
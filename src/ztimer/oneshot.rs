@@ -0,0 +1,225 @@
+//! Closures run once in interrupt context after a delay
+
+use core::cell::UnsafeCell;
+use core::marker::PhantomPinned;
+use core::pin::Pin;
+
+use super::{Clock, Ticks};
+
+/// A timer that runs a closure once, in interrupt context, after a configurable delay
+///
+/// The timer is set through [`.arm()`](Self::arm) on its pinned form, and removed when it is
+/// [canceled](Self::cancel) or dropped. Arming a pending timer again restarts its countdown; once
+/// the closure has run, a new one can be placed in the timer through
+/// [`.set_callback()`](Self::set_callback).
+///
+/// Arming needs a `'static` closure, as only then it is safe to run it if the timer's drop never
+/// happens. For shorter lived closures, see [`OneShot::scoped()`].
+///
+/// ```
+/// # #![no_std]
+/// # #![feature(start)]
+/// # #[start]
+/// # fn main(_argc: isize, _argv: *const *const u8) -> isize { panic!("Doc tests are not supposed to be run") }
+/// # use riot_wrappers::ztimer::{Clock, OneShot, Ticks};
+/// # use core::sync::atomic::{AtomicBool, Ordering};
+/// static FIRED: AtomicBool = AtomicBool::new(false);
+///
+/// let mut timer = core::pin::pin!(OneShot::new(Clock::msec_unbound(), || {
+///     FIRED.store(true, Ordering::Relaxed)
+/// }));
+/// timer.as_mut().arm(Ticks(100));
+/// ```
+pub struct OneShot<F: FnOnce() + Send, const HZ: u32> {
+    clock: Clock<HZ>,
+    // From the .arm(), the timer's arg points to the OneShot itself
+    timer: UnsafeCell<riot_sys::ztimer_t>,
+    // Taken in the interrupt when the timer fires; all other accesses happen either while the
+    // timer is not set, or with interrupts disabled.
+    callback: UnsafeCell<Option<F>>,
+    _phantom: PhantomPinned,
+}
+
+// unsafe: The callback is Send, and only ever accessed as per the comment on the field
+unsafe impl<F: FnOnce() + Send, const HZ: u32> Send for OneShot<F, HZ> {}
+unsafe impl<F: FnOnce() + Send, const HZ: u32> Sync for OneShot<F, HZ> {}
+
+impl<F: FnOnce() + Send, const HZ: u32> OneShot<F, HZ> {
+    /// Create a timer that is not armed yet
+    pub fn new(clock: Clock<HZ>, callback: F) -> Self {
+        OneShot {
+            clock,
+            // This is zero-initialized, which is the more efficient mode for ztimer_t.
+            timer: UnsafeCell::new(Default::default()),
+            callback: UnsafeCell::new(Some(callback)),
+            _phantom: PhantomPinned,
+        }
+    }
+
+    /// Run a function with a timer for a closure that may be shorter lived than `'static`.
+    ///
+    /// The timer is removed when the function returns, before anything the closure borrows can go
+    /// away. Like with [`thread::scope()`](crate::thread::scope), the function can not end the
+    /// timer's existence on its own; it only gets to use it through a [ScopedOneShot].
+    ///
+    /// ```
+    /// # #![no_std]
+    /// # #![feature(start)]
+    /// # #[start]
+    /// # fn main(_argc: isize, _argv: *const *const u8) -> isize { panic!("Doc tests are not supposed to be run") }
+    /// # use riot_wrappers::ztimer::{Clock, OneShot, Ticks};
+    /// # use core::sync::atomic::{AtomicBool, Ordering};
+    /// let fired = AtomicBool::new(false);
+    /// let msec = Clock::msec();
+    /// OneShot::scoped(*msec, || fired.store(true, Ordering::Relaxed), |mut timer| {
+    ///     timer.arm(Ticks(10));
+    ///     msec.sleep(Ticks(20));
+    /// });
+    /// assert!(fired.load(Ordering::Relaxed));
+    /// ```
+    pub fn scoped<R>(
+        clock: Clock<HZ>,
+        callback: F,
+        body: impl FnOnce(ScopedOneShot<'_, F, HZ>) -> R,
+    ) -> R {
+        let timer = core::pin::pin!(Self::new(clock, callback));
+        body(ScopedOneShot { timer })
+        // The timer is dropped here, removing it from the clock
+    }
+
+    /// Set the timer, and start it over if it was already set.
+    ///
+    /// The caller needs to ensure that the callback outlives any possibility of it being run,
+    /// which is the case if it is 'static (for the pinned self is then either dropped, which
+    /// removes the timer, or lives forever).
+    unsafe fn arm_unchecked(self: Pin<&mut Self>, ticks: Ticks<HZ>) {
+        extern "C" fn caller<F: FnOnce() + Send, const HZ: u32>(arg: *mut crate::libc::c_void) {
+            // unsafe: Set to the pinned OneShot in arm, and the timer is removed before the
+            // OneShot goes away
+            let this = unsafe { &*(arg as *const OneShot<F, HZ>) };
+            // unsafe: In the interrupt, nothing else accesses the callback (see field comment)
+            if let Some(callback) = unsafe { &mut *this.callback.get() }.take() {
+                callback();
+            }
+        }
+
+        let arg = &*self as *const Self as *mut crate::libc::c_void;
+        let timer = self.timer.get();
+        // unsafe: OK per C API
+        unsafe { riot_sys::ztimer_remove(self.clock.0, timer) };
+        // With the timer removed, the callback can not run any more between checking and setting.
+        assert!(
+            !self.fired(),
+            "OneShot timer was armed after its callback has run"
+        );
+        // unsafe: The timer is not set while it is modified, and then set as per the C API; it
+        // stays valid as self is pinned.
+        unsafe {
+            (*timer).callback = Some(caller::<F, HZ>);
+            (*timer).arg = arg;
+            riot_sys::ztimer_set(self.clock.0, timer, ticks.0);
+        }
+    }
+
+    /// Remove the timer if it is armed, and return whether it was.
+    ///
+    /// If it was, the callback has not run, and the timer can be armed again.
+    #[doc(alias = "ztimer_remove")]
+    pub fn cancel(self: Pin<&mut Self>) -> bool {
+        // unsafe: OK per C API
+        unsafe { riot_sys::ztimer_remove(self.clock.0, self.timer.get()) }
+    }
+
+    /// Replace the callback, canceling the timer if it is armed.
+    ///
+    /// The previous callback is returned if it has not run yet.
+    pub fn set_callback(self: Pin<&mut Self>, callback: F) -> Option<F> {
+        self.remove();
+        // unsafe: The timer is not set, so nothing else accesses the callback
+        unsafe { (*self.callback.get()).replace(callback) }
+    }
+
+    /// Whether the timer is armed and waiting to run the callback
+    #[doc(alias = "ztimer_is_set")]
+    pub fn is_armed(&self) -> bool {
+        // unsafe: C function only reads
+        unsafe { riot_sys::ztimer_is_set(self.clock.0, self.timer.get()) != 0 }
+    }
+
+    /// Whether the callback has run (and no new one has been set since)
+    pub fn fired(&self) -> bool {
+        crate::interrupt::free(|_| {
+            // unsafe: With interrupts disabled, the callback is not taken concurrently
+            unsafe { &*self.callback.get() }.is_none()
+        })
+    }
+
+    fn remove(&self) {
+        // unsafe: OK per C API; removing works no matter whether the timer is set
+        unsafe { riot_sys::ztimer_remove(self.clock.0, self.timer.get()) };
+    }
+}
+
+impl<F: FnOnce() + Send + 'static, const HZ: u32> OneShot<F, HZ> {
+    /// Set the timer to run the callback after the given number of ticks.
+    ///
+    /// If the timer is already armed, its countdown starts over.
+    ///
+    /// ## Panics
+    ///
+    /// ... if the callback has already run. (A new callback can be set through
+    /// [`.set_callback()`](Self::set_callback)).
+    #[doc(alias = "ztimer_set")]
+    pub fn arm(self: Pin<&mut Self>, ticks: Ticks<HZ>) {
+        // unsafe: The callback is 'static
+        unsafe { self.arm_unchecked(ticks) }
+    }
+}
+
+impl<F: FnOnce() + Send, const HZ: u32> Drop for OneShot<F, HZ> {
+    fn drop(&mut self) {
+        self.remove();
+        // and then drop the fields
+    }
+}
+
+/// Access to a [OneShot] timer created by [`OneShot::scoped()`]
+///
+/// This offers the same methods as the timer, without the need for the callback to be `'static`.
+pub struct ScopedOneShot<'a, F: FnOnce() + Send, const HZ: u32> {
+    timer: Pin<&'a mut OneShot<F, HZ>>,
+}
+
+impl<'a, F: FnOnce() + Send, const HZ: u32> ScopedOneShot<'a, F, HZ> {
+    /// Set the timer to run the callback after the given number of ticks.
+    ///
+    /// See [`OneShot::arm()`].
+    #[doc(alias = "ztimer_set")]
+    pub fn arm(&mut self, ticks: Ticks<HZ>) {
+        // unsafe: The timer is owned by OneShot::scoped, which drops it before the callback's
+        // lifetime ends.
+        unsafe { self.timer.as_mut().arm_unchecked(ticks) }
+    }
+
+    /// See [`OneShot::cancel()`]
+    #[doc(alias = "ztimer_remove")]
+    pub fn cancel(&mut self) -> bool {
+        self.timer.as_mut().cancel()
+    }
+
+    /// See [`OneShot::set_callback()`]
+    pub fn set_callback(&mut self, callback: F) -> Option<F> {
+        self.timer.as_mut().set_callback(callback)
+    }
+
+    /// See [`OneShot::is_armed()`]
+    #[doc(alias = "ztimer_is_set")]
+    pub fn is_armed(&self) -> bool {
+        self.timer.is_armed()
+    }
+
+    /// See [`OneShot::fired()`]
+    pub fn fired(&self) -> bool {
+        self.timer.fired()
+    }
+}
@@ -0,0 +1,188 @@
+//! Limiting the time futures may take
+
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+
+use pin_project::pin_project;
+
+use super::{AsyncSleep, Clock, LockedClock, NascentAsyncSleep, Ticks, Timestamp};
+
+/// Error produced by a [Timeout] when the time ran out before the future completed
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Elapsed;
+
+impl core::fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Timeout elapsed")
+    }
+}
+
+/// A future that runs an inner future, unless that takes longer than a given time
+///
+/// This is created by [`Clock::timeout()`] or [`Deadline::timeout()`]. If the timeout elapses
+/// first, the inner future is not polled any more, and dropped along with this.
+#[pin_project]
+pub struct Timeout<const HZ: u32, F> {
+    #[pin]
+    future: F,
+    #[pin]
+    sleep: AsyncSleep<HZ>,
+    // For timeouts created by a [Deadline], the deadline's start and duration (with the clock kept
+    // acquired), from which the sleep's time is set when first polled
+    deadline: Option<(LockedClock<HZ>, Timestamp<HZ>, Ticks<HZ>)>,
+}
+
+impl<const HZ: u32, F: Future> Future for Timeout<HZ, F> {
+    type Output = Result<F::Output, Elapsed>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+        if let Some((clock, start, duration)) = this.deadline.take() {
+            // Time starts running now, so this is the time left for the sleep.
+            this.sleep.set(AsyncSleep::NeverPolled(NascentAsyncSleep {
+                clock: clock.0,
+                ticks: remaining(&clock, start, duration),
+            }));
+        }
+        // Polling the future first, so that a future that is ready right away always succeeds
+        if let Poll::Ready(output) = this.future.poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+        this.sleep.poll(cx).map(|()| Err(Elapsed))
+    }
+}
+
+impl<const HZ: u32> Clock<HZ> {
+    /// Run a future, but give up on it if it does not complete within the given time.
+    ///
+    /// As with [`.sleep_async()`](Self::sleep_async), time starts running when the returned future
+    /// is first polled.
+    ///
+    /// ```
+    /// # #![no_std]
+    /// # #![feature(start)]
+    /// # #[start]
+    /// # fn main(_argc: isize, _argv: *const *const u8) -> isize { panic!("Doc tests are not supposed to be run") }
+    /// # use riot_wrappers::ztimer::{Clock, Ticks};
+    /// # async fn receive() -> u32 { 42 }
+    /// # async {
+    /// match Clock::msec().timeout(Ticks(500), receive()).await {
+    ///     Ok(value) => riot_wrappers::println!("Received {}", value),
+    ///     Err(_) => riot_wrappers::println!("Nothing received within 500ms"),
+    /// }
+    /// # };
+    /// ```
+    pub fn timeout<F: Future>(&self, duration: Ticks<HZ>, future: F) -> Timeout<HZ, F> {
+        Timeout {
+            future,
+            sleep: AsyncSleep::NeverPolled(NascentAsyncSleep {
+                clock: *self,
+                ticks: duration,
+            }),
+            deadline: None,
+        }
+    }
+
+    /// Create a deadline that expires after the given time from now.
+    ///
+    /// The clock is [acquired](Self::acquire) for as long as the deadline exists.
+    pub fn deadline(&self, duration: Ticks<HZ>) -> Deadline<HZ> {
+        let clock = self.acquire();
+        let start = clock.now();
+        Deadline {
+            clock,
+            start,
+            duration,
+        }
+    }
+}
+
+/// A point in time by which some operations need to be done
+///
+/// Unlike a [Timeout], this keeps track of the time already spent, so it can limit several
+/// consecutive operations to a total time, or a sequence of operations in which individual steps
+/// have their own shorter limits:
+///
+/// ```
+/// # #![no_std]
+/// # #![feature(start)]
+/// # #[start]
+/// # fn main(_argc: isize, _argv: *const *const u8) -> isize { panic!("Doc tests are not supposed to be run") }
+/// # use riot_wrappers::ztimer::{Clock, Elapsed, Ticks};
+/// # async fn send_request() {}
+/// # async fn receive_response() -> u32 { 42 }
+/// async fn exchange() -> Result<u32, Elapsed> {
+///     let deadline = Clock::msec().deadline(Ticks(2000));
+///     // Sending gets at most 100ms, but no more than is left of the total
+///     deadline.nested(Ticks(100)).timeout(send_request()).await?;
+///     deadline.timeout(receive_response()).await
+/// }
+/// ```
+pub struct Deadline<const HZ: u32> {
+    clock: LockedClock<HZ>,
+    start: Timestamp<HZ>,
+    duration: Ticks<HZ>,
+}
+
+impl<const HZ: u32> Deadline<HZ> {
+    /// Time left until the deadline, or zero if it has passed
+    ///
+    /// As with any [Timestamp] comparison, this is only accurate while the deadline is less than
+    /// 2³² ticks in the past.
+    pub fn remaining(&self) -> Ticks<HZ> {
+        remaining(&self.clock, self.start, self.duration)
+    }
+
+    /// Whether the deadline has passed
+    pub fn is_elapsed(&self) -> bool {
+        self.remaining().0 == 0
+    }
+
+    /// Run a future, but give up on it if it does not complete by the deadline.
+    ///
+    /// The remaining time is determined when the returned future is first polled. The clock stays
+    /// acquired for as long as the returned future exists, even if the deadline is dropped before.
+    pub fn timeout<F: Future>(&self, future: F) -> Timeout<HZ, F> {
+        Timeout {
+            future,
+            sleep: AsyncSleep::NeverPolled(NascentAsyncSleep {
+                clock: self.clock.0,
+                ticks: self.duration,
+            }),
+            deadline: Some((self.clock.0.acquire(), self.start, self.duration)),
+        }
+    }
+
+    /// Create a deadline that expires after the given time from now, or together with this
+    /// deadline, whichever comes first.
+    pub fn nested(&self, duration: Ticks<HZ>) -> Deadline<HZ> {
+        let clock = self.clock.0.acquire();
+        let start = clock.now();
+        let duration = Ticks(duration.0.min(self.remaining().0));
+        Deadline {
+            clock,
+            start,
+            duration,
+        }
+    }
+}
+
+/// Time left of a duration that started at a given time, or zero if it has passed
+fn remaining<const HZ: u32>(
+    clock: &LockedClock<HZ>,
+    start: Timestamp<HZ>,
+    duration: Ticks<HZ>,
+) -> Ticks<HZ> {
+    let spent = clock.now() - start;
+    Ticks(duration.0.saturating_sub(spent.0))
+}
+
+impl<const HZ: u32> core::fmt::Debug for Deadline<HZ> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Deadline")
+            .field("start", &self.start)
+            .field("duration", &self.duration)
+            .finish()
+    }
+}
@@ -0,0 +1,36 @@
+#![no_std]
+
+use riot_wrappers::executor::block_on;
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::ztimer::{Clock, Ticks};
+
+riot_main!(main);
+
+async fn main() -> Result<(), &'static str> {
+    let msec = Clock::msec();
+    let locked = msec.acquire();
+
+    let before = locked.now();
+    msec.sleep_async(Ticks(100)).await;
+    // Nesting is not recommended, but should work
+    let value = block_on(async {
+        msec.sleep_async(Ticks(100)).await;
+        42
+    });
+    msec.sleep_async(Ticks(100)).await;
+    let after = locked.now();
+    drop(locked);
+
+    if value != 42 {
+        return Err("Inner future produced wrong result");
+    }
+    let elapsed = (after - before).0;
+    println!("Sleeping asynchronously took {} ticks", elapsed);
+    if elapsed < 300 {
+        return Err("Futures completed too early");
+    }
+
+    println!("SUCCESS");
+    Ok(())
+}
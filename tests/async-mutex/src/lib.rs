@@ -0,0 +1,76 @@
+#![no_std]
+
+use core::future::Future;
+use core::pin::pin;
+use core::task::Poll;
+
+use riot_wrappers::executor::block_on;
+use riot_wrappers::mutex::AsyncMutex;
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::thread;
+use riot_wrappers::ztimer::{Clock, Ticks};
+
+riot_main!(main);
+
+static COUNTER: AsyncMutex<u32> = AsyncMutex::new(0);
+
+fn main() {
+    let msec = Clock::msec();
+
+    // Two tasks in one executor: The second needs to wait for the first to release the lock,
+    // which only works if waiting does not block the thread.
+    block_on(async {
+        let mut first = pin!(async {
+            let mut counter = COUNTER.lock().await;
+            msec.sleep_async(Ticks(20)).await;
+            *counter += 1;
+        });
+        let mut second = pin!(async {
+            // Let the first task get the lock
+            msec.sleep_async(Ticks(5)).await;
+            assert!(COUNTER.try_lock().is_none(), "Lock is held by first task");
+            let counter = COUNTER.lock().await;
+            assert!(*counter == 1, "Second task ran before the first was done");
+        });
+        let mut first_done = false;
+        let mut second_done = false;
+        core::future::poll_fn(|cx| {
+            first_done = first_done || first.as_mut().poll(cx).is_ready();
+            second_done = second_done || second.as_mut().poll(cx).is_ready();
+            if first_done && second_done {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    });
+    println!("Tasks took turns");
+
+    // A regular thread waiting for a lock held by a task
+    let mut stack = [0u8; 4096];
+    thread::scope(|scope| {
+        let adder = thread::Builder::new()
+            .name(c"adder")
+            .priority_relative(-1)
+            .spawn_scoped(scope, &mut stack, || {
+                msec.sleep(Ticks(5));
+                *COUNTER.blocking_lock() += 10;
+            })
+            .expect("Failed to spawn adder thread");
+
+        block_on(async {
+            let counter = COUNTER.lock().await;
+            msec.sleep_async(Ticks(20)).await;
+            assert!(*counter == 1, "Thread modified the value while locked");
+        });
+
+        scope.join(adder);
+    });
+    let counter = COUNTER.try_lock().expect("Mutex is free again");
+    assert!(*counter == 11);
+    println!("Thread and task took turns");
+
+    println!("SUCCESS");
+}
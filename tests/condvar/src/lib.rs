@@ -0,0 +1,46 @@
+#![no_std]
+
+use riot_wrappers::condvar::Condvar;
+use riot_wrappers::cstr::cstr;
+use riot_wrappers::mutex::Mutex;
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::thread;
+
+riot_main!(main);
+
+static COUNT: Mutex<u32> = Mutex::new(0);
+static CHANGED: Condvar = Condvar::new();
+
+fn main() {
+    let mut stack = [0u8; 8192];
+    let mut producer = || {
+        for _ in 0..3 {
+            *COUNT.lock() += 1;
+            CHANGED.notify_one();
+        }
+    };
+
+    thread::scope(|scope| {
+        // Holding the lock keeps the higher priority producer from running ahead
+        let guard = COUNT.lock();
+
+        let producer = scope
+            .spawn(
+                &mut stack,
+                &mut producer,
+                cstr!("producer"),
+                (riot_sys::THREAD_PRIORITY_MAIN - 1) as _,
+                0,
+            )
+            .expect("Failed to spawn producer thread");
+
+        let guard = CHANGED.wait_while(guard, |count| *count < 3);
+        println!("Count reached {}", *guard);
+        drop(guard);
+
+        scope.reap(producer);
+    });
+
+    println!("SUCCESS");
+}
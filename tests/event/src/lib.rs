@@ -0,0 +1,45 @@
+#![no_std]
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use riot_wrappers::event::{Event, EventQueue};
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::thread::StartToken;
+use riot_wrappers::ztimer::{Clock, Ticks};
+
+riot_main!(main);
+
+fn main(tok: StartToken) -> ! {
+    let (_tok, mut semantics) = tok.take_flag_semantics();
+
+    let queue = EventQueue::new();
+
+    let count = AtomicU32::new(0);
+    let mut counter = || {
+        let new = count.load(Ordering::Relaxed) + 1;
+        count.store(new, Ordering::Relaxed);
+        println!("Counting event ran ({} times)", new);
+    };
+    let counting = Event::new(&queue, &mut counter as &mut (dyn FnMut() + Send));
+    // Posting twice before the queue is processed only queues it once
+    counting.post();
+    counting.post();
+
+    let finish = Event::new(&queue, || {
+        assert_eq!(
+            count.load(Ordering::Relaxed),
+            1,
+            "Event posted twice was handled more than once"
+        );
+        println!("SUCCESS");
+    });
+    // Posting from an interrupt
+    let msec = Clock::msec();
+    msec.set_during(|| finish.post(), Ticks(10), || msec.sleep(Ticks(20)));
+
+    // Flags handed out to other components do not collide with the one the queue needs
+    let (_receiver, _sender) = semantics.split_off();
+
+    queue.loop_forever(&mut semantics)
+}
@@ -0,0 +1,34 @@
+#![no_std]
+
+use riot_wrappers::executor::block_on;
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::ztimer::{Clock, Ticks};
+
+riot_main!(main);
+
+fn main() {
+    let msec = Clock::msec();
+    let locked = msec.acquire();
+
+    let before = locked.now();
+    let value = block_on(async {
+        msec.sleep_async(Ticks(100)).await;
+        // Nesting is not recommended, but should work
+        let inner = block_on(async {
+            msec.sleep_async(Ticks(100)).await;
+            42
+        });
+        msec.sleep_async(Ticks(100)).await;
+        inner
+    });
+    let after = locked.now();
+    drop(locked);
+
+    assert!(value == 42);
+    let elapsed = (after - before).0;
+    println!("Sleeping asynchronously took {} ticks", elapsed);
+    assert!(elapsed >= 300, "Futures completed too early");
+
+    println!("SUCCESS");
+}
@@ -0,0 +1,35 @@
+#![no_std]
+
+use riot_wrappers::interrupt::{self, IrqMutex};
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::ztimer::{Clock, Ticks};
+
+riot_main!(main);
+
+static EVENTS: IrqMutex<u32> = IrqMutex::new(0);
+
+fn main() {
+    EVENTS.with(|events| *events += 1);
+
+    // Access from an interrupt
+    let msec = Clock::msec();
+    msec.set_during(
+        || EVENTS.with(|events| *events += 10),
+        Ticks(10),
+        || msec.sleep(Ticks(20)),
+    );
+    assert!(EVENTS.with(|events| *events) == 11);
+
+    interrupt::free(|cs| {
+        let mut events = EVENTS.lock(cs);
+        *events += 100;
+        assert!(
+            EVENTS.try_lock(cs).is_none(),
+            "Locked twice in one critical section"
+        );
+    });
+    assert!(EVENTS.with(|events| *events) == 111);
+
+    println!("SUCCESS");
+}
@@ -0,0 +1,42 @@
+#![no_std]
+
+use riot_wrappers::error::ETIMEDOUT;
+use riot_wrappers::isrpipe::BytePipe;
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::ztimer::{Clock, Ticks};
+
+riot_main!(main);
+
+fn main() {
+    let mut pipe: BytePipe<8> = BytePipe::new();
+    let (producer, mut consumer) = pipe.split();
+    let mut buf = [0; 16];
+
+    // Writing from a thread; the pipe takes only as much as fits
+    assert!(producer.write(b"0123456789") == 8);
+    assert!(producer.write_byte(b'x') == Err(b'x'));
+    assert!(consumer.available() == 8);
+    assert!(consumer.try_read(&mut buf) == 8);
+    assert!(&buf[..8] == b"01234567");
+    println!("Wrote and read from a thread");
+
+    // Blocking until an interrupt writes
+    let msec = Clock::msec();
+    let n = msec.set_during(
+        || {
+            producer.write(b"hi");
+        },
+        Ticks(10),
+        || embedded_io::Read::read(&mut consumer, &mut buf).unwrap(),
+    );
+    assert!(&buf[..n] == b"hi");
+    println!("Read data written in an interrupt");
+
+    // Nothing arrives
+    let result = consumer.read_timeout(&mut buf, Ticks(10_000));
+    assert!(result == Err(ETIMEDOUT), "Read did not time out");
+    println!("Read timed out");
+
+    println!("SUCCESS");
+}
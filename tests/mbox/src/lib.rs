@@ -0,0 +1,80 @@
+#![no_std]
+
+use core::pin::pin;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use riot_wrappers::mbox::Mailbox;
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::ztimer::{Clock, Ticks};
+
+riot_main!(main);
+
+static DROPPED: AtomicU32 = AtomicU32::new(0);
+
+/// A value that counts how often values of its type were dropped
+struct Counted(u32);
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        DROPPED.store(DROPPED.load(Ordering::Relaxed) + 1, Ordering::Relaxed);
+    }
+}
+
+fn dropped() -> u32 {
+    DROPPED.load(Ordering::Relaxed)
+}
+
+fn main() {
+    let mut mailbox = pin!(Mailbox::<u32, 2>::new());
+    let (tx, rx) = mailbox.as_mut().split();
+
+    assert!(rx.try_get().is_none());
+
+    tx.put(1);
+    tx.try_put(2).unwrap();
+    assert!(tx.try_put(3) == Err(3), "Full mailbox accepted value");
+    assert!(rx.available() == 2);
+    assert!(rx.get() == 1);
+    assert!(rx.try_get() == Some(2));
+
+    // Putting from an interrupt
+    let received = Clock::msec().set_during(|| tx.try_put(4).unwrap(), Ticks(10), || rx.get());
+    assert!(received == 4);
+    println!("Received value put in from interrupt");
+
+    // Splitting again empties the mailbox
+    tx.put(5);
+    let (_tx, rx) = mailbox.as_mut().split();
+    assert!(rx.try_get().is_none());
+
+    // Values are dropped exactly once, wherever they end up
+    {
+        let mut counted = pin!(Mailbox::<Counted, 2>::new());
+        let (tx, rx) = counted.as_mut().split();
+        tx.put(Counted(1));
+        let taken = rx.get();
+        assert!(taken.0 == 1);
+        assert!(dropped() == 0, "Value was dropped while in the mailbox");
+        drop(taken);
+        assert!(dropped() == 1);
+
+        tx.put(Counted(2));
+        let (tx, _rx) = counted.as_mut().split();
+        assert!(dropped() == 2, "Value was not dropped when splitting again");
+
+        tx.put(Counted(3));
+        tx.put(Counted(4));
+        let refused = tx.try_put(Counted(5)).unwrap_err();
+        assert!(refused.0 == 5);
+        assert!(dropped() == 2, "Refused value was dropped");
+        drop(refused);
+        assert!(dropped() == 3);
+    }
+    assert!(
+        dropped() == 5,
+        "Values left in the mailbox were not dropped along with it"
+    );
+
+    println!("SUCCESS");
+}
@@ -0,0 +1,95 @@
+#![no_std]
+
+use core::future::Future;
+use core::pin::pin;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+use riot_wrappers::executor::block_on;
+use riot_wrappers::msg::v2::{MessageSemantics, ReceivePort};
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::thread::StartToken;
+use riot_wrappers::ztimer::{Clock, Ticks};
+
+riot_main!(main);
+
+type NumberReceived = ReceivePort<u32, 1>;
+
+/// Set by the waker of the hand-rolled executor below
+static WOKEN: AtomicBool = AtomicBool::new(false);
+
+static VTABLE: RawWakerVTable = RawWakerVTable::new(
+    |_| RawWaker::new(core::ptr::null(), &VTABLE),
+    |_| WOKEN.store(true, Ordering::Relaxed),
+    |_| WOKEN.store(true, Ordering::Relaxed),
+    |_| (),
+);
+
+fn main(tok: StartToken) -> ! {
+    tok.with_message_queue::<4, _>(|tok| {
+        let (_tok, semantics) = tok.take_msg_semantics();
+        let (semantics, numbers, send_number): (_, NumberReceived, _) = semantics.split_off();
+
+        let msec = Clock::msec();
+
+        // Nothing is sent, so the timer wins
+        let received = block_on(async {
+            let mut receive = pin!(semantics.receive_async());
+            let mut timeout = pin!(msec.sleep_async(Ticks(20)));
+            core::future::poll_fn(|cx| {
+                if let Poll::Ready(message) = receive.as_mut().poll(cx) {
+                    return Poll::Ready(Some(message));
+                }
+                timeout.as_mut().poll(cx).map(|()| None)
+            })
+            .await
+        });
+        assert!(received.is_none(), "Message received out of nowhere");
+        println!("Timed out while no message was sent");
+
+        // Sending from an interrupt while the executor is waiting
+        let number = msec.set_during(
+            || send_number.try_send(42).expect("Queue is empty"),
+            Ticks(10),
+            || {
+                block_on(async {
+                    semantics
+                        .receive_async()
+                        .await
+                        .decode(&numbers, |_sender, number| number)
+                        .expect("Unexpected message")
+                })
+            },
+        );
+        assert!(number == 42);
+        println!("Received {} from an interrupt", number);
+
+        // Polling through an executor other than block_on
+        // unsafe: The vtable functions do not access the data
+        let waker = unsafe { Waker::from_raw(RawWaker::new(core::ptr::null(), &VTABLE)) };
+        let mut cx = Context::from_waker(&waker);
+        let mut receive = pin!(semantics.receive_async());
+        assert!(receive.as_mut().poll(&mut cx).is_pending());
+        assert!(
+            WOKEN.load(Ordering::Relaxed),
+            "Other executor was not asked to poll again"
+        );
+        send_number.try_send(23).expect("Queue is empty");
+        let Poll::Ready(message) = receive.as_mut().poll(&mut cx) else {
+            panic!("Message was not received");
+        };
+        let number = message
+            .decode(&numbers, |_sender, number| number)
+            .expect("Unexpected message");
+        assert!(number == 23);
+        println!("Received {} through another executor", number);
+
+        println!("SUCCESS");
+
+        // The port can not be recombined into the semantics, so this thread can not end.
+        loop {
+            riot_wrappers::thread::sleep();
+        }
+    })
+}
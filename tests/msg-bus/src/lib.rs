@@ -0,0 +1,72 @@
+#![no_std]
+
+use riot_wrappers::msg::bus::{MsgBus, SubscriptionSlot};
+use riot_wrappers::msg::v2::{MessageSemantics, ReceivePort};
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::thread::{self, StartToken};
+use riot_wrappers::ztimer::{Clock, Ticks};
+
+riot_main!(main);
+
+type Bus = MsgBus<u32, 42>;
+static BUS: Bus = MsgBus::new();
+const UP: u16 = Bus::typeno(0);
+const DOWN: u16 = Bus::typeno(1);
+
+fn main(tok: StartToken) -> ! {
+    tok.with_message_queue::<8, _>(|tok| {
+        let (_tok, semantics) = tok.take_msg_semantics();
+        let (semantics, up, up_port): (_, ReceivePort<u32, UP>, _) = semantics.split_off();
+        let (semantics, _down, _down_port): (_, ReceivePort<u32, DOWN>, _) = semantics.split_off();
+
+        let mut slot = core::pin::pin!(SubscriptionSlot::default());
+        let subscription = BUS.subscribe(up_port, slot.as_mut());
+
+        // Publishing from a thread; nobody listens to DOWN
+        let mut stack = [0u8; 4096];
+        let (sent_up, sent_down) = thread::scope(|scope| {
+            let publisher = thread::Builder::new()
+                .name(c"publisher")
+                .spawn_scoped(scope, &mut stack, || {
+                    (BUS.publish::<UP>(1), BUS.publish::<DOWN>(2))
+                })
+                .expect("Failed to spawn publisher thread");
+            scope.join(publisher)
+        });
+        assert!(sent_up == 1, "Subscriber was not reached");
+        assert!(sent_down == 0, "Message sent to unsubscribed topic");
+
+        // Publishing from an interrupt
+        Clock::msec().set_during(
+            || {
+                BUS.publish::<UP>(3);
+            },
+            Ticks(10),
+            || Clock::msec().sleep(Ticks(20)),
+        );
+
+        let mut sum = 0;
+        for _ in 0..2 {
+            sum += semantics
+                .receive()
+                .decode(&up, |_sender, value| value)
+                .expect("Unexpected message");
+        }
+        assert!(sum == 4);
+        println!("Received published values");
+
+        // After unsubscribing, nothing is sent any more
+        let _up_port = subscription.unsubscribe();
+        assert!(
+            BUS.publish::<UP>(4) == 0,
+            "Message sent after unsubscribing"
+        );
+
+        println!("SUCCESS");
+
+        loop {
+            thread::sleep();
+        }
+    })
+}
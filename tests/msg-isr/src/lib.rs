@@ -0,0 +1,59 @@
+#![no_std]
+
+use riot_wrappers::msg::v2::{MessageSemantics, ReceivePort, Sender};
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::thread::{InIsr, StartToken};
+use riot_wrappers::ztimer::{Clock, Ticks};
+
+riot_main!(main);
+
+type NumberReceived = ReceivePort<u32, 1>;
+
+fn main(tok: StartToken) -> ! {
+    tok.with_message_queue::<4, _>(|tok| {
+        let (_tok, semantics) = tok.take_msg_semantics();
+        let (semantics, numbers, send_number): (_, NumberReceived, _) = semantics.split_off();
+
+        assert!(send_number.queue_capacity() == 4);
+        assert!(send_number.queued() == 0);
+
+        // Filling the queue from an interrupt until it overflows
+        let msec = Clock::msec();
+        msec.set_during(
+            || {
+                let isr = InIsr::new().expect("Timer callbacks run in interrupt context");
+                for number in 0..4 {
+                    send_number
+                        .try_send_isr(number, isr)
+                        .expect("Queue has space");
+                }
+                assert!(
+                    send_number.try_send_isr(4, isr) == Err(4),
+                    "Full queue accepted message"
+                );
+            },
+            Ticks(10),
+            || msec.sleep(Ticks(20)),
+        );
+        assert!(send_number.queued() == 4);
+        println!("Queue filled from an interrupt");
+
+        for expected in 0..4 {
+            let (sender, number) = semantics
+                .receive()
+                .decode(&numbers, |sender, number| (sender, number))
+                .expect("Unexpected message");
+            assert!(sender == Sender::ISR, "Message not marked as sent from ISR");
+            assert!(number == expected);
+        }
+        assert!(send_number.queued() == 0);
+
+        println!("SUCCESS");
+
+        // The port can not be recombined into the semantics, so this thread can not end.
+        loop {
+            riot_wrappers::thread::sleep();
+        }
+    })
+}
@@ -0,0 +1,60 @@
+#![no_std]
+
+use riot_wrappers::msg::v2::{MessageSemantics, RpcPort};
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::thread::{self, StartToken};
+
+riot_main!(main);
+
+type Doubler = RpcPort<u32, u32, 42>;
+
+fn main(tok: StartToken) -> ! {
+    let (_tok, semantics) = tok.take_msg_semantics();
+    let (semantics, doubler, call_doubler): (_, Doubler, _) = semantics.split_off_rpc();
+
+    let mut stack = [0u8; 4096];
+    let (sum, unanswered) = thread::scope(|scope| {
+        // More urgent than main, so it is already blocked in the call when main receives
+        let client = thread::Builder::new()
+            .name(c"client")
+            .priority_relative(-1)
+            .spawn_scoped(scope, &mut stack, || {
+                let sum = (1..=3)
+                    .map(|i| call_doubler.call(i).expect("Request was not answered"))
+                    .sum::<u32>();
+                let unanswered = [call_doubler.call(4), call_doubler.call(5)];
+                (sum, unanswered)
+            })
+            .expect("Failed to spawn client thread");
+
+        for _ in 0..3 {
+            semantics
+                .receive()
+                .decode_rpc(&doubler, |_sender, request, reply| reply.reply(2 * request))
+                .expect("Unexpected message");
+        }
+
+        // Dropping the reply handle unblocks the caller
+        semantics
+            .receive()
+            .decode_rpc(&doubler, |_sender, _request, reply| drop(reply))
+            .expect("Unexpected message");
+        // So does dropping the request without decoding it
+        drop(semantics.receive());
+
+        scope.join(client)
+    });
+
+    println!("Sum of doubled values is {}", sum);
+    assert!(sum == 12);
+    println!("Unanswered requests returned {:?}", unanswered);
+    assert!(unanswered == [None, None]);
+
+    println!("SUCCESS");
+
+    // The RPC port can not be recombined into the semantics, so this thread can not end.
+    loop {
+        thread::sleep();
+    }
+}
@@ -0,0 +1,55 @@
+#![no_std]
+
+use core::pin::pin;
+
+use riot_wrappers::msg::v2::{MessageSemantics, ReceivePort, ScheduledMessage};
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::thread::StartToken;
+use riot_wrappers::ztimer::{Clock, Ticks};
+
+riot_main!(main);
+
+type Retransmit = ReceivePort<u32, 1>;
+
+fn main(tok: StartToken) -> ! {
+    tok.with_message_queue::<4, _>(|tok| {
+        let (_tok, semantics) = tok.take_msg_semantics();
+        let (semantics, retransmit, retransmit_port): (_, Retransmit, _) = semantics.split_off();
+
+        let msec = Clock::msec();
+
+        // Nothing is scheduled
+        assert!(semantics.receive_timeout(&msec, Ticks(10)).is_none());
+        println!("Receiving timed out");
+
+        let mut timer = pin!(ScheduledMessage::new(&retransmit_port, *msec));
+        timer.as_mut().schedule(1, Ticks(30));
+        assert!(timer.is_scheduled());
+        // Too early
+        assert!(semantics.receive_timeout(&msec, Ticks(10)).is_none());
+        let attempt = semantics
+            .receive_timeout(&msec, Ticks(100))
+            .expect("Scheduled message arrived")
+            .decode(&retransmit, |_sender, attempt| attempt)
+            .expect("Unexpected message");
+        assert!(attempt == 1);
+        assert!(!timer.is_scheduled());
+        println!("Received scheduled value {}", attempt);
+
+        // Rescheduling replaces the value, and canceling takes it back
+        assert!(timer.as_mut().schedule(2, Ticks(30)).is_none());
+        assert!(timer.as_mut().schedule(3, Ticks(30)) == Some(2));
+        assert!(timer.as_mut().cancel() == Some(3));
+        assert!(timer.as_mut().cancel().is_none());
+        assert!(semantics.receive_timeout(&msec, Ticks(50)).is_none());
+        println!("Canceled value was not sent");
+
+        println!("SUCCESS");
+
+        // The port can not be recombined into the semantics, so this thread can not end.
+        loop {
+            riot_wrappers::thread::sleep();
+        }
+    })
+}
@@ -4,6 +4,7 @@ use riot_wrappers::mutex::Mutex;
 use riot_wrappers::println;
 use riot_wrappers::riot_main;
 use riot_wrappers::thread::InThread;
+use riot_wrappers::ztimer::{Clock, Ticks};
 
 riot_main!(main);
 
@@ -24,5 +25,23 @@ fn main() {
     *m2.lock() = 4;
     assert!(*m2.lock() == 4);
 
+    let msec = Clock::msec();
+    let l4 = m2.lock();
+    assert!(m2.lock_timeout(&msec, Ticks(10)).is_none());
+    drop(l4);
+    assert!(m2.lock_timeout(&msec, Ticks(10)).is_some());
+
+    // Canceling a lock attempt that is stuck waiting
+    let cancelable = m2.cancelable_lock();
+    let l5 = m2.lock();
+    let canceled = msec.set_during(
+        || cancelable.cancel(),
+        Ticks(10),
+        || cancelable.lock().is_none(),
+    );
+    assert!(canceled, "Lock attempt was not canceled");
+    drop(l5);
+    assert!(cancelable.lock().is_none(), "Canceled lock is usable again");
+
     println!("SUCCESS");
 }
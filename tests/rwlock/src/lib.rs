@@ -0,0 +1,52 @@
+#![no_std]
+
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::rwlock::RwLock;
+use riot_wrappers::ztimer::{Clock, Ticks};
+
+riot_main!(main);
+
+static LOCK: RwLock<u32> = RwLock::new(0);
+
+fn main() {
+    *LOCK.write() = 1;
+
+    let r1 = LOCK.read();
+    let r2 = LOCK.read();
+    assert!(*r1 == 1 && *r2 == 1);
+    assert!(
+        LOCK.try_write().is_none(),
+        "Writer admitted while readers are active"
+    );
+    drop(r1);
+    assert!(
+        LOCK.try_write().is_none(),
+        "Writer admitted while a reader is active"
+    );
+    drop(r2);
+
+    let w = LOCK
+        .try_write()
+        .expect("Writer not admitted to unused lock");
+    assert!(
+        LOCK.try_read().is_none(),
+        "Reader admitted while writer is active"
+    );
+    drop(w);
+
+    // Reading from an interrupt while a thread reads
+    let r3 = LOCK.read();
+    Clock::msec().set_during(
+        || assert!(*LOCK.try_read().unwrap() == 1),
+        Ticks(10),
+        || Clock::msec().sleep(Ticks(20)),
+    );
+    drop(r3);
+    println!("Read from interrupt");
+
+    *LOCK.write() += 1;
+    assert!(*LOCK.read() == 2);
+
+    println!("SUCCESS");
+}
@@ -0,0 +1,48 @@
+#![no_std]
+
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::thread::{self, sched_cb};
+use riot_wrappers::ztimer::{Clock, Ticks};
+
+riot_main!(main);
+
+static TRACER: static_cell::StaticCell<sched_cb::Tracer<1000, 16>> = static_cell::StaticCell::new();
+
+fn main() {
+    let me = thread::get_pid();
+    let schedules_before = me.sched_stats().expect("Main thread exists").schedules();
+
+    let tracer = TRACER.init(sched_cb::Tracer::new(Clock::msec().acquire()));
+    let mut registration = sched_cb::register(tracer);
+
+    let mut stack = [0u8; 4096];
+    let worker = thread::scope(|scope| {
+        let worker = thread::Builder::new()
+            .name(c"worker")
+            .priority_relative(-1)
+            .spawn_scoped(scope, &mut stack, || {
+                Clock::msec().sleep(Ticks(10));
+                thread::get_pid()
+            })
+            .expect("Failed to spawn worker thread");
+        scope.join(worker)
+    });
+
+    let recorded = registration.with(|tracer| tracer.recorded());
+    assert!(recorded > 0, "No context switches recorded");
+
+    let tracer = registration.unregister();
+    tracer.dump();
+    let schedules_after = me.sched_stats().expect("Main thread exists").schedules();
+    assert!(
+        schedules_after > schedules_before,
+        "Scheduler statistics stopped while the hook was registered"
+    );
+    assert!(
+        tracer.entries().any(|e| e.next == worker),
+        "Switch to worker thread was not recorded"
+    );
+
+    println!("SUCCESS");
+}
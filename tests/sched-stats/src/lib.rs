@@ -0,0 +1,65 @@
+#![no_std]
+
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::thread::{self, SchedSnapshot};
+use riot_wrappers::ztimer::{Clock, Ticks};
+
+riot_main!(main);
+
+fn main() {
+    let me = thread::get_pid();
+    let stats = me.sched_stats().expect("Main thread exists");
+    assert!(stats.schedules() > 0, "Main thread was never scheduled");
+
+    let before = SchedSnapshot::take();
+    // Sleep once (letting other threads run), then keep the CPU busy for a while
+    let msec = Clock::msec();
+    msec.sleep(Ticks(50));
+    let locked = msec.acquire();
+    let start = locked.now();
+    while (locked.now() - start).0 < 50 {}
+    drop(locked);
+    let after = SchedSnapshot::take();
+
+    let mut total = 0;
+    let mut total_runtime = 0;
+    let mut own = 0;
+    let mut own_runtime = 0;
+    for usage in after.since(&before) {
+        println!(
+            "{:?} ({:?}): {} schedules, {} per mille",
+            usage.pid(),
+            usage.pid().get_name(),
+            usage.schedules(),
+            usage.permille()
+        );
+        total += usage.permille();
+        total_runtime += usage.runtime_us();
+        if usage.pid() == me {
+            own = usage.permille();
+            own_runtime = usage.runtime_us();
+        }
+    }
+    // The busy loop is still main's ongoing time slice when the snapshot is taken, and is counted
+    // nevertheless.
+    assert!(own_runtime > 0, "Busy loop was not counted");
+    assert!(own_runtime <= total_runtime, "Thread ran longer than all");
+    assert!(own > 0 && own <= total, "Thread share exceeds the total");
+    assert!(total <= 1000, "Shares add up to more than the whole");
+
+    // Statistics only ever grow
+    let later = SchedSnapshot::take();
+    let [before, after, later] =
+        [&before, &after, &later].map(|snapshot| snapshot.get(me).expect("Main thread exists"));
+    assert!(
+        before.runtime_us() < after.runtime_us() && after.runtime_us() <= later.runtime_us(),
+        "Runtime did not grow"
+    );
+    assert!(
+        before.schedules() <= after.schedules() && after.schedules() <= later.schedules(),
+        "Number of schedules did not grow"
+    );
+
+    println!("SUCCESS");
+}
@@ -0,0 +1,39 @@
+#![no_std]
+
+use riot_wrappers::error::{EAGAIN, ETIMEDOUT};
+use riot_wrappers::executor::block_on;
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::sema::Semaphore;
+use riot_wrappers::ztimer::{Clock, Ticks};
+
+riot_main!(main);
+
+static SEMA: Semaphore = Semaphore::new(1);
+
+fn main() {
+    let msec = Clock::msec();
+
+    SEMA.wait();
+    assert!(SEMA.value() == 0);
+    assert!(SEMA.try_wait() == Err(EAGAIN));
+    assert!(SEMA.wait_timeout(&msec, Ticks(10)) == Err(ETIMEDOUT));
+
+    // Posting from an interrupt
+    msec.set_during(
+        || SEMA.post().unwrap(),
+        Ticks(10),
+        || SEMA.wait_timeout(&msec, Ticks(100)).unwrap(),
+    );
+    println!("Semaphore posted from interrupt was taken");
+
+    // Posting from an interrupt to a waiting task
+    msec.set_during(
+        || SEMA.post().unwrap(),
+        Ticks(10),
+        || block_on(SEMA.acquire()),
+    );
+    println!("Semaphore posted from interrupt was acquired asynchronously");
+
+    println!("SUCCESS");
+}
@@ -0,0 +1,47 @@
+#![no_std]
+
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::thread;
+
+riot_main!(main);
+
+fn main() {
+    let mut stack_a = [0u8; 4096];
+    let mut stack_b = [0u8; 4096];
+    let numbers = [1u32, 2, 3, 4];
+
+    let (sum, len) = thread::scope(|scope| {
+        // Less urgent than main, so joining actually needs to wait for it
+        let summer = thread::Builder::new()
+            .name(c"summer")
+            .priority_relative(1)
+            .stacktest(true)
+            .spawn_scoped(scope, &mut stack_a, || numbers.iter().sum::<u32>())
+            .expect("Failed to spawn summer thread");
+
+        let counter = thread::Builder::new()
+            .name(c"counter")
+            .priority_relative(-1)
+            .sleeping(true)
+            .spawn_scoped(scope, &mut stack_b, || numbers.len())
+            .expect("Failed to spawn counter thread");
+        counter.pid().wakeup().expect("Thread is sleeping");
+
+        let summer_pid = summer.pid();
+        let sum = scope.join(summer);
+        // Joining only returns once the thread is gone and its stack is not used any more
+        assert!(
+            summer_pid.status().is_err(),
+            "Joined thread is still around"
+        );
+
+        (sum, scope.join(counter))
+    });
+
+    println!("Sum of {} numbers is {}", len, sum);
+    assert!(sum == 10);
+    assert!(len == 4);
+
+    println!("SUCCESS");
+}
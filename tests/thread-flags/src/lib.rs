@@ -0,0 +1,37 @@
+#![no_std]
+
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::thread::{EndToken, StartToken};
+use riot_wrappers::ztimer::{Clock, Ticks};
+
+riot_main!(main);
+
+fn main(tok: StartToken) -> ((), EndToken) {
+    let (tok, mut semantics) = tok.take_flag_semantics();
+
+    let (rx_a, tx_a) = semantics.split_off();
+    let (rx_b, tx_b) = semantics.split_off();
+    assert!(rx_a.mask() != rx_b.mask(), "Flags were assigned twice");
+
+    // Setting from the own thread
+    tx_a.set();
+    assert!(rx_a.try_take() == rx_a.mask());
+    assert!(rx_a.try_take() == 0);
+
+    // Setting from an interrupt
+    let msec = Clock::msec();
+    let mask_b = rx_b.mask();
+    let rx = rx_a.merge(rx_b);
+    let set = msec.set_during(|| tx_b.set(), Ticks(10), || rx.wait_any());
+    assert!(set == mask_b, "Wrong flag was set");
+    println!("Flag set in ISR was received");
+
+    semantics.return_sender(tx_a);
+    semantics.return_sender(tx_b);
+    semantics.return_receiver(rx);
+    let tok = tok.return_flag_semantics(semantics);
+
+    println!("SUCCESS");
+    ((), tok.can_end())
+}
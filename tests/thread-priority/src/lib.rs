@@ -0,0 +1,52 @@
+#![no_std]
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::thread;
+
+riot_main!(main);
+
+static WORKER_RAN: AtomicBool = AtomicBool::new(false);
+
+fn main() {
+    let me = thread::get_pid();
+    let original = me.priority().unwrap();
+
+    {
+        let _boost = me.boost_priority(original - 2).unwrap();
+        assert!(me.priority().unwrap() == original - 2);
+
+        // Boosting to a less urgent priority does not change anything
+        let _nested = me.boost_priority(original).unwrap();
+        assert!(me.priority().unwrap() == original - 2);
+    }
+    assert!(
+        me.priority().unwrap() == original,
+        "Priority was not restored"
+    );
+    println!("Boost restored the priority");
+
+    let mut stack = [0u8; 4096];
+    thread::scope(|scope| {
+        // Less urgent than main, so it does not run on its own until main blocks
+        let worker = thread::Builder::new()
+            .name(c"worker")
+            .priority_relative(1)
+            .spawn_scoped(scope, &mut stack, || {
+                WORKER_RAN.store(true, Ordering::Relaxed)
+            })
+            .expect("Failed to spawn worker thread");
+        assert!(!WORKER_RAN.load(Ordering::Relaxed));
+
+        // Making it more urgent than main switches to it right away
+        worker.pid().set_priority(original - 1).unwrap();
+        assert!(WORKER_RAN.load(Ordering::Relaxed), "Worker did not preempt");
+
+        scope.join(worker);
+    });
+    println!("Raising another thread's priority preempted the current one");
+
+    println!("SUCCESS");
+}
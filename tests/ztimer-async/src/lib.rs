@@ -1,5 +1,7 @@
 #![no_std]
 
+use core::future::Future;
+
 use riot_wrappers::println;
 use riot_wrappers::riot_main;
 
@@ -38,6 +40,29 @@ async fn amain(spawner: embassy_executor::Spawner) {
         (after - before).0
     );
     drop(locked);
+
+    println!("Waiting 50000 ticks on the usec timer, while also waiting 10ms");
+    let usec = Clock::usec();
+    let locked = usec.acquire();
+    let before = locked.now();
+    // The short sleep wakes the task early, so the long sleep gets polled while its timer is still
+    // pending (on a clock other than ZTIMER_MSEC), and needs to tell that it is not done yet.
+    let mut long = core::pin::pin!(usec.sleep_async(Ticks(50_000)));
+    let mut short = core::pin::pin!(msec.sleep_async(Ticks(10)));
+    let mut short_done = false;
+    core::future::poll_fn(|cx| {
+        if !short_done {
+            short_done = short.as_mut().poll(cx).is_ready();
+        }
+        long.as_mut().poll(cx)
+    })
+    .await;
+    let after = locked.now();
+    println!("That took {} ticks.", (after - before).0);
+    assert!(short_done);
+    assert!((after - before).0 >= 50_000, "Sleep completed early");
+    drop(locked);
+
     println!("And now for something more complex...");
 
     spawner
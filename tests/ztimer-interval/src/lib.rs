@@ -0,0 +1,57 @@
+#![no_std]
+
+use riot_wrappers::executor::block_on;
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::ztimer::{Clock, Ticks};
+
+riot_main!(main);
+
+fn main() {
+    let msec = Clock::msec();
+    let locked = msec.acquire();
+
+    block_on(async {
+        let start = locked.now();
+        let mut interval = msec.interval(Ticks(20));
+        for _ in 0..5 {
+            assert!(interval.tick().await == 0, "No ticks should be missed");
+            // Work that takes time does not delay later ticks
+            msec.spin(Ticks(5));
+        }
+        let spent = locked.now() - start;
+        // Had the work delayed the ticks, this would have taken 125 ms; anything shorter leaves
+        // room for scheduling delays.
+        assert!(
+            spent.0 >= 100 && spent.0 < 125,
+            "5 ticks took {} ms",
+            spent.0
+        );
+        println!("5 ticks took {} ms", spent.0);
+
+        // Falling behind (by at least 3 periods, one of which is the tick that is returned)
+        msec.sleep(Ticks(70));
+        let missed = interval.tick().await;
+        assert!(missed >= 2, "Missed {} ticks", missed);
+        println!("Missed {} ticks", missed);
+        // The interval is back on its original schedule
+        let before = locked.now();
+        assert!(interval.tick().await == 0);
+        let waited = locked.now() - before;
+        // At most a period, with generous room for scheduling delays
+        assert!(waited.0 < 40, "Waited {} ms", waited.0);
+
+        // Changing the period
+        interval.reset();
+        interval.set_period(Ticks(50));
+        assert!(interval.period().0 == 50);
+        let before = locked.now();
+        interval.tick().await;
+        let waited = locked.now() - before;
+        // A full new period, but not two
+        assert!(waited.0 >= 50 && waited.0 < 100, "Waited {} ms", waited.0);
+        println!("Changed period to {} ms", interval.period().0);
+    });
+
+    println!("SUCCESS");
+}
@@ -0,0 +1,75 @@
+#![no_std]
+
+use core::pin::pin;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::thread::InIsr;
+use riot_wrappers::ztimer::{Clock, OneShot, Ticks};
+
+riot_main!(main);
+
+static COUNT: AtomicU32 = AtomicU32::new(0);
+
+fn count() {
+    assert!(InIsr::new().is_ok(), "Callback runs in interrupt context");
+    COUNT.fetch_add(1, Ordering::Relaxed);
+}
+
+fn main() {
+    let msec = Clock::msec();
+
+    let mut timer = pin!(OneShot::new(*msec, count));
+    timer.as_mut().arm(Ticks(20));
+    assert!(timer.is_armed());
+    // Re-arming starts the countdown over
+    msec.sleep(Ticks(10));
+    timer.as_mut().arm(Ticks(20));
+    msec.sleep(Ticks(15));
+    assert!(
+        COUNT.load(Ordering::Relaxed) == 0,
+        "Re-armed timer fired early"
+    );
+    msec.sleep(Ticks(10));
+    assert!(COUNT.load(Ordering::Relaxed) == 1);
+    assert!(timer.fired() && !timer.is_armed());
+    println!("Timer fired once");
+
+    // Canceling
+    assert!(timer.as_mut().set_callback(count).is_none());
+    timer.as_mut().arm(Ticks(10));
+    assert!(timer.as_mut().cancel());
+    assert!(!timer.as_mut().cancel());
+    msec.sleep(Ticks(20));
+    assert!(COUNT.load(Ordering::Relaxed) == 1, "Canceled timer fired");
+    assert!(!timer.fired());
+    println!("Canceled timer did not fire");
+
+    // Dropping removes the timer
+    {
+        let mut timer = pin!(OneShot::new(*msec, count));
+        timer.as_mut().arm(Ticks(10));
+    }
+    msec.sleep(Ticks(20));
+    assert!(COUNT.load(Ordering::Relaxed) == 1, "Dropped timer fired");
+    println!("Dropped timer did not fire");
+
+    // Borrowing from the stack
+    let local = AtomicU32::new(0);
+    OneShot::scoped(
+        *msec,
+        || {
+            local.fetch_add(1, Ordering::Relaxed);
+        },
+        |mut timer| {
+            timer.arm(Ticks(10));
+            msec.sleep(Ticks(20));
+            assert!(timer.fired());
+        },
+    );
+    assert!(local.load(Ordering::Relaxed) == 1);
+    println!("Scoped timer fired");
+
+    println!("SUCCESS");
+}
@@ -0,0 +1,71 @@
+#![no_std]
+
+use riot_wrappers::executor::block_on;
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::ztimer::{Clock, Elapsed, Ticks};
+
+riot_main!(main);
+
+fn main() {
+    let msec = Clock::msec();
+    let usec = Clock::usec();
+
+    block_on(async {
+        // Completes in time
+        let result = msec
+            .timeout(Ticks(100), async {
+                msec.sleep_async(Ticks(10)).await;
+                42
+            })
+            .await;
+        assert!(result == Ok(42));
+        println!("Future completed before the timeout");
+
+        // Takes too long; this also exercises a non-millisecond clock for the sleep itself
+        let result = usec
+            .timeout(Ticks(10_000), usec.sleep_async(Ticks(100_000)))
+            .await;
+        assert!(result == Err(Elapsed));
+        println!("Future timed out");
+
+        // A deadline shared by several steps
+        let locked = msec.acquire();
+        let start = locked.now();
+        let deadline = msec.deadline(Ticks(100));
+        deadline
+            .timeout(msec.sleep_async(Ticks(60)))
+            .await
+            .expect("First step fits");
+        assert!(deadline.remaining().0 <= 40);
+        // The nested deadline is limited by the outer one
+        let nested = deadline.nested(Ticks(1000));
+        assert!(nested.remaining().0 <= 40);
+        let result = nested.timeout(msec.sleep_async(Ticks(60))).await;
+        assert!(result == Err(Elapsed));
+        assert!(deadline.is_elapsed());
+        let spent = locked.now() - start;
+        assert!(
+            spent.0 >= 100 && spent.0 < 150,
+            "Deadline took {} ms",
+            spent.0
+        );
+        println!("Deadline expired after {} ms", spent.0);
+
+        // Time that passes before a deadline's timeout is first polled still counts
+        let start = locked.now();
+        let deadline = msec.deadline(Ticks(100));
+        let late = deadline.timeout(msec.sleep_async(Ticks(1000)));
+        msec.sleep_async(Ticks(50)).await;
+        assert!(late.await == Err(Elapsed));
+        let spent = locked.now() - start;
+        assert!(
+            spent.0 >= 100 && spent.0 < 140,
+            "Deadline took {} ms when polled late",
+            spent.0
+        );
+        println!("Late polled deadline expired after {} ms", spent.0);
+    });
+
+    println!("SUCCESS");
+}
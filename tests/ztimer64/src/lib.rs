@@ -0,0 +1,46 @@
+#![no_std]
+
+use core::time::Duration;
+
+use riot_wrappers::println;
+use riot_wrappers::riot_main;
+use riot_wrappers::ztimer::{Clock64, Ticks64, Timestamp64};
+
+riot_main!(main);
+
+fn main() {
+    // Conversions are exact on clocks whose tick length is a whole number of nanoseconds
+    let duration = Duration::new(86400 * 1000, 123_456_000);
+    let ticks = Ticks64::<1_000_000>::try_from(duration).unwrap();
+    assert!(ticks == Ticks64(86_400_000_123_456_000));
+    assert!(Duration::from(ticks) == duration);
+    // Otherwise, conversion from a duration rounds up
+    assert!(Ticks64::<3>::try_from(Duration::from_millis(1)).unwrap() == Ticks64(1));
+    assert!(Ticks64::<1_000_000>::try_from(Duration::MAX).is_err());
+    println!("Conversions are correct");
+
+    let msec = Clock64::msec();
+    let usec = Clock64::usec();
+
+    let start = msec.now64();
+    msec.sleep(Ticks64(100));
+    let after_sleep = msec.now64();
+    assert!(after_sleep - start >= Ticks64(100));
+
+    let target = after_sleep + Ticks64(50);
+    msec.sleep_until(target);
+    assert!(msec.now64() >= target);
+    // Sleeping until a time in the past returns right away
+    msec.sleep_until(Timestamp64(0));
+    println!(
+        "Slept for {:?}, uptime is {:?}",
+        (msec.now64() - start).to_duration(),
+        msec.now64().since_start()
+    );
+
+    let before = usec.now64();
+    usec.sleep(Ticks64(2_000));
+    assert!(usec.now64() - before >= Ticks64(2_000));
+
+    println!("SUCCESS");
+}